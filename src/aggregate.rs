@@ -0,0 +1,240 @@
+use alloc::format;
+use alloc::string::ToString;
+use alloc::vec;
+use alloc::vec::Vec;
+
+use crate::{cell, Cell, ColumnIndex, ModifyRows, Table};
+
+/// How to combine the values that fall into the same bucket.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Aggregation {
+    /// Number of values in the bucket.
+    Count,
+    /// Sum of the numeric values. Other values are ignored. The sum is an
+    /// integer if every value was and it fits in an `i64`.
+    Sum,
+    /// The first value seen in the bucket.
+    First,
+}
+
+#[derive(Default)]
+struct Accumulator {
+    count: usize,
+    int_sum: i64,
+    float_sum: f64,
+    any_float: bool,
+    first: Option<Cell>,
+}
+
+impl Accumulator {
+    fn push(&mut self, value: &Cell) {
+        self.count += 1;
+        match value.as_i64() {
+            Some(n) => match self.int_sum.checked_add(n) {
+                Some(sum) => self.int_sum = sum,
+                // Carry on in floating point rather than wrap to a wrong total.
+                None => {
+                    self.float_sum += self.int_sum as f64 + n as f64;
+                    self.int_sum = 0;
+                    self.any_float = true;
+                }
+            },
+            None => if let Some(n) = value.as_f64() {
+                self.float_sum += n;
+                self.any_float = true;
+            },
+        }
+        if self.first.is_none() {
+            self.first = Some(value.clone());
+        }
+    }
+
+    fn finish(&self, aggregation: Aggregation) -> Cell {
+        match aggregation {
+            Aggregation::Count => self.count.into(),
+            Aggregation::Sum if self.any_float => Cell::Float(self.int_sum as f64 + self.float_sum),
+            Aggregation::Sum => Cell::Int(self.int_sum),
+            Aggregation::First => self.first.clone().unwrap_or_default(),
+        }
+    }
+}
+
+/// The cell for a bucket that received no values.
+fn empty(aggregation: Aggregation) -> Cell {
+    match aggregation {
+        Aggregation::Count => Cell::Int(0),
+        Aggregation::Sum | Aggregation::First => Cell::Empty,
+    }
+}
+
+fn position_or_insert(keys: &mut Vec<Cell>, key: &Cell) -> usize {
+    match keys.iter().position(|k| k == key) {
+        Some(i) => i,
+        None => {
+            keys.push(key.clone());
+            keys.len() - 1
+        }
+    }
+}
+
+impl<T, const N: usize> Table<T, N> {
+    /// Build a crosstab. Each distinct value of `row_key` becomes a row, each distinct value of
+    /// `column_key` becomes a column, and the cells are the `value` column aggregated over
+    /// every row that shares both keys. Keys appear in the order they are first seen.
+    pub fn pivot(&self, row_key: usize, column_key: usize, value: usize, aggregation: Aggregation) -> Table<ModifyRows, N> {
+        let mut row_keys: Vec<Cell> = Vec::new();
+        let mut column_keys: Vec<Cell> = Vec::new();
+        let mut buckets: Vec<Vec<Option<Accumulator>>> = Vec::new();
+        for row in self.rows.iter() {
+            let r = position_or_insert(&mut row_keys, cell(row, row_key));
+            let c = position_or_insert(&mut column_keys, cell(row, column_key));
+            if r == buckets.len() {
+                buckets.push(Vec::new());
+            }
+            let bucket = &mut buckets[r];
+            if c >= bucket.len() {
+                bucket.resize_with(c + 1, || None);
+            }
+            bucket[c].get_or_insert_with(Accumulator::default).push(cell(row, value));
+        }
+
+        let mut headers = Vec::with_capacity(column_keys.len() + 1);
+        headers.push(self.headers.get(row_key)
+            .map(|h| h.text.clone())
+            .unwrap_or_default()
+            .into());
+        headers.extend(column_keys.iter().map(|k| k.to_string().into()));
+
+        let rows = row_keys.into_iter()
+            .zip(buckets)
+            .map(|(key, bucket)| {
+                let mut cells = Vec::with_capacity(column_keys.len() + 1);
+                cells.push(key);
+                cells.extend((0..column_keys.len()).map(|c| match bucket.get(c) {
+                    Some(Some(acc)) => acc.finish(aggregation),
+                    _ => empty(aggregation),
+                }));
+                cells
+            })
+            .collect();
+
+        Table::from_parts(headers, rows, self.config.clone())
+    }
+}
+
+impl<T, const N: usize> Table<T, N> {
+    fn groups(&self, column: usize) -> Vec<(Cell, Vec<usize>)> {
+        let mut keys: Vec<Cell> = Vec::new();
+        let mut groups: Vec<(Cell, Vec<usize>)> = Vec::new();
+        for (i, row) in self.rows.iter().enumerate() {
+            let g = position_or_insert(&mut keys, cell(row, column));
+            if g == groups.len() {
+                groups.push((keys[g].clone(), Vec::new()));
+            }
+            groups[g].1.push(i);
+        }
+        groups
+    }
+
+    /// The distinct values of `column` and how many rows hold each, in the
+    /// order they first appear. Returns `None` if there is no such column.
+    pub fn distinct<C: ColumnIndex>(&self, column: C) -> Option<Vec<(Cell, usize)>> {
+        let index = column.index_in(&self.headers)?;
+        Some(self.groups(index)
+            .into_iter()
+            .map(|(value, rows)| (value, rows.len()))
+            .collect())
+    }
+
+    fn aggregate(&self, rows: &[usize], column: usize, aggregation: Aggregation) -> Cell {
+        let mut acc = Accumulator::default();
+        for &r in rows {
+            acc.push(cell(&self.rows[r], column));
+        }
+        acc.finish(aggregation)
+    }
+
+    /// Collapse the table to one row per distinct value of `column`. The result has the group
+    /// column followed by one column per `(column, aggregation)` pair.
+    pub fn group_by(&self, column: usize, aggregations: &[(usize, Aggregation)]) -> Table<ModifyRows, N> {
+        let header_text = |i: usize| self.headers.get(i)
+            .map(|h| h.text.clone())
+            .unwrap_or_default();
+        let mut headers = Vec::with_capacity(aggregations.len() + 1);
+        headers.push(header_text(column).into());
+        headers.extend(aggregations.iter().map(|&(c, _)| header_text(c).into()));
+
+        let rows = self.groups(column).into_iter()
+            .map(|(key, members)| {
+                let mut cells = Vec::with_capacity(aggregations.len() + 1);
+                cells.push(key);
+                cells.extend(aggregations.iter()
+                    .map(|&(c, agg)| self.aggregate(&members, c, agg)));
+                cells
+            })
+            .collect();
+
+        Table::from_parts(headers, rows, self.config.clone())
+    }
+
+    /// Reorder the rows into sections by distinct value of `column`, each followed by a subtotal
+    /// row. The subtotal row is labelled `"<key> total"` in the group column and holds the
+    /// aggregated values in the columns named by `aggregations`.
+    pub fn group_by_subtotals(&self, column: usize, aggregations: &[(usize, Aggregation)]) -> Table<ModifyRows, N> {
+        let ncols = self.headers.len();
+        let mut rows = Vec::with_capacity(self.rows.len());
+        for (key, members) in self.groups(column) {
+            rows.extend(members.iter().map(|&r| self.rows[r].to_vec()));
+            let mut subtotal = vec![Cell::Empty; ncols];
+            if let Some(label) = subtotal.get_mut(column) {
+                *label = format!("{key} total").into();
+            }
+            for &(c, agg) in aggregations {
+                if let Some(cell) = subtotal.get_mut(c) {
+                    *cell = self.aggregate(&members, c, agg);
+                }
+            }
+            rows.push(subtotal);
+        }
+
+        Table::from_parts(self.headers.clone(), rows, self.config.clone())
+    }
+}
+
+/// Summary statistics over the numeric cells of a column.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ColumnStats {
+    /// Number of numeric cells.
+    pub count: usize,
+    pub min: f64,
+    pub max: f64,
+    pub mean: f64,
+}
+
+impl<T, const N: usize> Table<T, N> {
+    /// Compute min/max/mean over the numeric cells of column `index`. `Str` cells are parsed, and
+    /// those that don't parse are skipped. Returns `None` if the column has no numeric cells.
+    pub fn column_stats(&self, index: usize) -> Option<ColumnStats> {
+        let mut count = 0;
+        let mut sum = 0.0;
+        let mut min = f64::INFINITY;
+        let mut max = f64::NEG_INFINITY;
+        for row in self.rows.iter() {
+            if let Some(n) = cell(row, index).as_f64() {
+                count += 1;
+                sum += n;
+                min = min.min(n);
+                max = max.max(n);
+            }
+        }
+        if count == 0 {
+            return None;
+        }
+        Some(ColumnStats {
+            count,
+            min,
+            max,
+            mean: sum / count as f64,
+        })
+    }
+}
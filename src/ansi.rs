@@ -0,0 +1,78 @@
+use alloc::borrow::Cow;
+use alloc::string::String;
+use alloc::vec::Vec;
+
+/// `s` without escape sequences, borrowed if it has none.
+pub(crate) fn strip_str(s: &str) -> Cow<'_, str> {
+    if !s.contains('\x1b') {
+        return Cow::Borrowed(s);
+    }
+    Cow::Owned(String::from_utf8_lossy(&strip(s)).into_owned())
+}
+
+/// `s` with its escape sequences removed.
+#[cfg(feature = "ansi")]
+pub(crate) fn strip(s: &str) -> Vec<u8> {
+    strip_ansi_escapes::strip(s).expect("Failed to strip escape sequences")
+}
+
+#[cfg(not(feature = "ansi"))]
+pub(crate) fn strip(s: &str) -> Vec<u8> {
+    strip_common(s)
+}
+
+/// Remove the common escape sequences without `strip_ansi_escapes`: CSI
+/// (`ESC [ … final`), OSC (`ESC ] … BEL` or `ESC ] … ESC \`), character set
+/// designations and two-byte escapes.
+#[cfg(any(not(feature = "ansi"), test))]
+fn strip_common(s: &str) -> Vec<u8> {
+    let mut out = String::with_capacity(s.len());
+    let mut chars = s.chars();
+    while let Some(c) = chars.next() {
+        if c != '\x1b' {
+            out.push(c);
+            continue;
+        }
+        match chars.next() {
+            Some('[') => {
+                for c in chars.by_ref() {
+                    if ('\x40'..='\x7e').contains(&c) {
+                        break;
+                    }
+                }
+            }
+            Some(']') => {
+                let mut esc = false;
+                for c in chars.by_ref() {
+                    if c == '\x07' || (esc && c == '\\') {
+                        break;
+                    }
+                    esc = c == '\x1b';
+                }
+            }
+            // Character set designations like `ESC ( B`.
+            Some(c) if ('\x20'..='\x2f').contains(&c) => {
+                for c in chars.by_ref() {
+                    if !('\x20'..='\x2f').contains(&c) {
+                        break;
+                    }
+                }
+            }
+            Some(c) if !c.is_ascii() => out.push(c),
+            _ => {}
+        }
+    }
+    out.into_bytes()
+}
+
+#[cfg(all(test, feature = "ansi"))]
+mod tests {
+    use super::strip_common;
+
+    #[test]
+    fn test_strip_common() {
+        for s in ["plain", "\x1b[1;31mred\x1b[0m", "\x1b]8;;https://example.com\x1b\\link\x1b]8;;\x07", "\x1b(Bé\x1b[K"] {
+            assert_eq!(strip_common(s), strip_ansi_escapes::strip(s).unwrap(), "{s:?}");
+        }
+    }
+}
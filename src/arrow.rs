@@ -0,0 +1,96 @@
+use arrow_array::cast::AsArray;
+use arrow_array::types::{Float64Type, Int64Type, UInt64Type};
+use arrow_array::{Array, RecordBatch};
+use arrow_cast::display::{ArrayFormatter, FormatOptions};
+use arrow_schema::{ArrowError, DataType};
+
+use crate::{Cell, ColumnType, Config, Header, ModifyRows, Table};
+
+impl<const N: usize> Table<ModifyRows, N> {
+    /// Build a table from a record batch, with a header per schema field.
+    /// Integer, float and boolean columns are typed, so numbers are
+    /// right-aligned; other types are shown with Arrow's display formatting.
+    pub fn from_record_batch(batch: &RecordBatch) -> Result<Self, ArrowError> {
+        let schema = batch.schema();
+        let headers: Vec<Header> = schema.fields().iter()
+            .map(|field| Header {
+                text: field.name().clone(),
+                column_type: column_type(field.data_type()),
+                ..Header::default()
+            })
+            .collect();
+        let columns = batch.columns().iter()
+            .map(|array| cells(array.as_ref()))
+            .collect::<Result<Vec<_>, _>>()?;
+        let rows = (0..batch.num_rows())
+            .map(|i| columns.iter().map(|column| column[i].clone()).collect())
+            .collect();
+        Ok(Table::from_parts(headers, rows, Config::default()))
+    }
+}
+
+fn column_type(data_type: &DataType) -> Option<ColumnType> {
+    if data_type.is_integer() {
+        Some(ColumnType::Int)
+    } else if data_type.is_floating() {
+        Some(ColumnType::Float)
+    } else if *data_type == DataType::Boolean {
+        Some(ColumnType::Bool)
+    } else {
+        None
+    }
+}
+
+fn cells(array: &dyn Array) -> Result<Vec<Cell>, ArrowError> {
+    let data_type = array.data_type();
+    let typed = |i: usize, value: &dyn Fn(usize) -> Cell| if array.is_null(i) { Cell::Empty } else { value(i) };
+    let len = array.len();
+    Ok(match data_type {
+        DataType::Boolean => {
+            let array = array.as_boolean();
+            (0..len).map(|i| typed(i, &|i| array.value(i).into())).collect()
+        }
+        DataType::UInt64 => {
+            let array = array.as_primitive::<UInt64Type>();
+            (0..len).map(|i| typed(i, &|i| array.value(i).into())).collect()
+        }
+        _ if data_type.is_integer() => {
+            let cast = arrow_cast::cast(array, &DataType::Int64)?;
+            let array = cast.as_primitive::<Int64Type>();
+            (0..len).map(|i| typed(i, &|i| array.value(i).into())).collect()
+        }
+        _ if data_type.is_floating() => {
+            let cast = arrow_cast::cast(array, &DataType::Float64)?;
+            let array = cast.as_primitive::<Float64Type>();
+            (0..len).map(|i| typed(i, &|i| array.value(i).into())).collect()
+        }
+        _ => {
+            let formatter = ArrayFormatter::try_new(array, &FormatOptions::default())?;
+            (0..len).map(|i| typed(i, &|i| formatter.value(i).to_string().into())).collect()
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use arrow_array::{ArrayRef, Int32Array, RecordBatch, StringArray};
+
+    use crate::{tests::texts, Cell, ModifyRows, Table};
+
+    #[test]
+    fn test_from_record_batch() {
+        let batch = RecordBatch::try_from_iter([
+            ("host", Arc::new(StringArray::from(vec![Some("web-1"), None])) as ArrayRef),
+            ("port", Arc::new(Int32Array::from(vec![80, 5432])) as ArrayRef),
+        ]).unwrap();
+        let table: Table<ModifyRows> = Table::from_record_batch(&batch).unwrap();
+        assert_eq!(texts(&table), vec![vec!["web-1", "80"], vec!["", "5432"]]);
+        assert_eq!(table.get(1, 1), Some(&Cell::Int(5432)));
+        assert_eq!(table.to_string(),
+                   "host     port     \n".to_owned() +
+                       "web-1          80 \n" +
+                       "             5432 \n");
+    }
+}
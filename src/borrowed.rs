@@ -0,0 +1,135 @@
+use alloc::borrow::Cow;
+use alloc::vec::Vec;
+
+use crate::plain::PlainLines;
+use crate::{widen, width, Cell, ModifyRows, Table};
+
+/// A table whose cells borrow their text, so that rows taken from a parsed
+/// buffer can be printed without copying every string.
+///
+/// The headers and settings come from an ordinary [`Table`]. Cells are text
+/// only; a cell in a column with a [`format`](crate::Column::format) is
+/// formatted into an owned string.
+///
+/// ```
+/// use tabular2::{BorrowedTable, Table};
+///
+/// let input = String::from("web-1 up\ndb-1 down");
+/// let mut table = BorrowedTable::new(Table::new().header("Host").header("State"));
+/// for line in input.lines() {
+///     table.push_row(line.split(' '));
+/// }
+/// assert_eq!(table.to_string(), "Host     State    \nweb-1    up       \ndb-1     down     \n");
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BorrowedTable<'a, const N: usize = 0> {
+    table: Table<ModifyRows, N>,
+    rows: Vec<Vec<Cow<'a, str>>>,
+    /// Visible width of each cell, measured when the row was pushed.
+    cell_widths: Vec<Vec<usize>>,
+}
+
+impl<'a, const N: usize> BorrowedTable<'a, N> {
+    /// Take the headers and settings from `table`. Its rows are dropped.
+    pub fn new<T>(table: Table<T, N>) -> Self {
+        BorrowedTable {
+            table: Table::from_parts(table.headers, Vec::new(), table.config),
+            rows: Vec::new(),
+            cell_widths: Vec::new(),
+        }
+    }
+
+    pub fn row<I, S>(mut self, cells: I) -> Self
+        where I: IntoIterator<Item = S>,
+              S: Into<Cow<'a, str>> {
+        self.push_row(cells);
+        self
+    }
+
+    /// Append a row. As with [`Table`], a table without headers takes its
+    /// column count from its widest row, and otherwise cells past the last
+    /// header aren't shown.
+    pub fn push_row<I, S>(&mut self, cells: I)
+        where I: IntoIterator<Item = S>,
+              S: Into<Cow<'a, str>> {
+        let headers = &self.table.headers;
+        let cells: Vec<Cow<'a, str>> = cells.into_iter()
+            .enumerate()
+            .map(|(i, cell)| match headers.get(i).and_then(|h| h.format.as_ref()) {
+                Some(format) => Cow::Owned(format.apply(&cell.into())),
+                None => cell.into(),
+            })
+            .collect();
+        let cell_widths: Vec<usize> = cells.iter().map(|cell| width(cell, self.table.config.width_strategy)).collect();
+        if self.table.config.fixed_widths.is_none() {
+            widen(&mut self.table.column_widths, headers.is_empty(), &cell_widths);
+        }
+        self.rows.push(cells);
+        self.cell_widths.push(cell_widths);
+    }
+
+    /// Number of data rows, not counting the header.
+    pub fn row_count(&self) -> usize {
+        self.rows.len()
+    }
+
+    /// Iterate over the data rows.
+    pub fn rows(&self) -> impl Iterator<Item = &[Cow<'a, str>]> + '_ {
+        self.rows.iter().map(|row| row.as_slice())
+    }
+
+    /// Copy the cells into an ordinary table of `Str` cells.
+    pub fn into_owned(self) -> Table<ModifyRows, N> {
+        let rows = self.rows.into_iter()
+            .map(|row| row.into_iter().map(|cell| Cell::Str(cell.into_owned())).collect())
+            .collect();
+        Table::from_parts(self.table.headers, rows, self.table.config)
+    }
+}
+
+impl<const N: usize> core::fmt::Display for BorrowedTable<'_, N> {
+    fn fmt(&self, f: &mut core::fmt::Formatter) -> core::fmt::Result {
+        let table = &self.table;
+        let widths = table.display_widths();
+        let groups = table.column_groups(&widths);
+        let rendered = self.rows.len() - table.rows_left_out(self.rows.len());
+        let rows = self.rows.iter()
+            .zip(self.cell_widths.iter())
+            .take(rendered)
+            .map(|(row, cell_widths)| row.iter().zip(cell_widths).map(|(cell, w)| (Cow::Borrowed(cell.as_ref()), *w)));
+        let mut lines = PlainLines::new(table, rows, self.rows.len(), widths, groups);
+        while let Some(line) = lines.write_next(f) {
+            line?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{BorrowedTable, Column, Table};
+
+    #[test]
+    fn test_borrowed_table() {
+        let buffer = String::from("1,ok\n2,a much longer status");
+        let header = Table::new()
+            .header("Id")
+            .header(Column::new("Status").format(|s| s.to_uppercase()));
+        let table = buffer.lines().fold(BorrowedTable::new(header), |table, line| table.row(line.split(',')));
+        assert_eq!(table.rows().next().unwrap()[1], "OK");
+        let expected = table.to_string();
+        assert_eq!(expected,
+                   "Id       Status               \n".to_owned() +
+                       "1        OK                   \n" +
+                       "2        A MUCH LONGER STATUS \n");
+        assert_eq!(table.into_owned().to_string(), expected);
+    }
+
+    #[test]
+    fn test_borrowed_max_render_rows() {
+        let lines = ["a", "b", "c"];
+        let table = lines.iter().fold(BorrowedTable::new(Table::new().header("Name").max_render_rows(2)), |table, line| table.row([*line]));
+        assert_eq!(table.to_string(), "Name     \na        \nb        \n… 1 more row\n");
+        assert_eq!(table.to_string(), table.into_owned().to_string());
+    }
+}
@@ -0,0 +1,47 @@
+use std::borrow::Cow;
+use std::fmt::Display;
+
+use bumpalo::Bump;
+
+use crate::BorrowedTable;
+
+impl<'a, const N: usize> BorrowedTable<'a, N> {
+    /// Append a row of values formatted into `arena`, so that a table built
+    /// and printed once doesn't allocate a `String` per cell. The arena frees
+    /// everything at once when it is dropped or reset.
+    ///
+    /// ```
+    /// use bumpalo::Bump;
+    /// use tabular2::{BorrowedTable, Table};
+    ///
+    /// let arena = Bump::new();
+    /// let mut table = BorrowedTable::new(Table::new().header("Id").header("Load"));
+    /// table.push_display_row(&arena, [&1 as &dyn std::fmt::Display, &0.5]);
+    /// assert_eq!(table.to_string(), "Id       Load     \n1        0.5      \n");
+    /// ```
+    pub fn push_display_row<I, D>(&mut self, arena: &'a Bump, cells: I)
+        where I: IntoIterator<Item = D>,
+              D: Display {
+        self.push_row(cells.into_iter().map(|cell| {
+            Cow::Borrowed(bumpalo::format!(in arena, "{}", cell).into_bump_str())
+        }));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bumpalo::Bump;
+
+    use crate::{BorrowedTable, Table};
+
+    #[test]
+    fn test_push_display_row() {
+        let arena = Bump::new();
+        let mut table = BorrowedTable::new(Table::new().header("Id"));
+        for i in 0..3 {
+            table.push_display_row(&arena, [i * 100]);
+        }
+        assert_eq!(table.rows().map(|row| row[0].as_ref()).collect::<Vec<_>>(), vec!["0", "100", "200"]);
+        assert!(table.rows().all(|row| matches!(row[0], std::borrow::Cow::Borrowed(_))));
+    }
+}
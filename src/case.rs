@@ -0,0 +1,84 @@
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+
+/// Case conversion for headers generated from field names.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum HeaderCase {
+    /// Keep the field name as written.
+    #[default]
+    Unchanged,
+    /// `cpu_percent` becomes `Cpu Percent`.
+    Title,
+    /// `cpu_percent` becomes `Cpu percent`.
+    Sentence,
+    /// `cpu_percent` becomes `CPU PERCENT`.
+    Upper,
+    /// `cpuPercent` becomes `cpu percent`.
+    Lower,
+}
+
+impl HeaderCase {
+    /// Convert a field name. Words are split on `_`, `-`, spaces and lower-to-upper case changes,
+    /// so both `snake_case` and `camelCase` names work.
+    pub fn apply(&self, name: &str) -> String {
+        if *self == HeaderCase::Unchanged {
+            return name.to_string();
+        }
+        let words = words(name);
+        let words = words.iter().enumerate().map(|(i, word)| match self {
+            HeaderCase::Title => capitalize(word),
+            HeaderCase::Sentence if i == 0 => capitalize(word),
+            HeaderCase::Upper => word.to_uppercase(),
+            _ => word.to_lowercase(),
+        });
+        words.collect::<Vec<_>>().join(" ")
+    }
+}
+
+fn words(name: &str) -> Vec<&str> {
+    let mut words = Vec::new();
+    let mut start = 0;
+    let mut prev_lower = false;
+    for (i, c) in name.char_indices() {
+        if matches!(c, '_' | '-' | ' ') {
+            if start < i {
+                words.push(&name[start..i]);
+            }
+            start = i + c.len_utf8();
+            prev_lower = false;
+            continue;
+        }
+        if c.is_uppercase() && prev_lower {
+            words.push(&name[start..i]);
+            start = i;
+        }
+        prev_lower = c.is_lowercase() || c.is_ascii_digit();
+    }
+    if start < name.len() {
+        words.push(&name[start..]);
+    }
+    words
+}
+
+fn capitalize(word: &str) -> String {
+    let mut chars = word.chars();
+    match chars.next() {
+        Some(first) => first.to_uppercase().chain(chars.flat_map(char::to_lowercase)).collect(),
+        None => String::new(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::HeaderCase;
+
+    #[test]
+    fn test_header_case() {
+        assert_eq!(HeaderCase::Title.apply("cpu_percent"), "Cpu Percent");
+        assert_eq!(HeaderCase::Title.apply("createdAt"), "Created At");
+        assert_eq!(HeaderCase::Sentence.apply("last-seen_at"), "Last seen at");
+        assert_eq!(HeaderCase::Upper.apply("id"), "ID");
+        assert_eq!(HeaderCase::Lower.apply("lastSeen"), "last seen");
+        assert_eq!(HeaderCase::Unchanged.apply("cpu_percent"), "cpu_percent");
+    }
+}
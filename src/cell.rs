@@ -0,0 +1,349 @@
+use alloc::borrow::Cow;
+use alloc::string::{String, ToString};
+use alloc::sync::Arc;
+use core::cmp::Ordering;
+use core::hash::{Hash, Hasher};
+
+use crate::{Alignment, Style};
+
+/// A single value in a table.
+///
+/// Numbers and booleans keep their type, so sorting, totals and statistics
+/// work on the values themselves rather than re-parsing the rendered text.
+#[derive(Debug, Clone, Default)]
+pub enum Cell {
+    Str(String),
+    /// Text shared with other cells, as stored by an
+    /// [`interned`](crate::Column::intern) column. Behaves like `Str`.
+    Shared(Arc<str>),
+    /// Text stored inline when it is short, without a heap allocation. With
+    /// the `compact_str` feature, text converted into a cell is stored this
+    /// way. Behaves like `Str`.
+    #[cfg(feature = "compact_str")]
+    Compact(compact_str::CompactString),
+    Int(i64),
+    Float(f64),
+    Bool(bool),
+    #[default]
+    Empty,
+}
+
+impl Cell {
+    /// A text cell from bytes that should be UTF-8. Invalid sequences become
+    /// U+FFFD; see [`InvalidTextPolicy`](crate::InvalidTextPolicy).
+    pub fn from_utf8_lossy(bytes: &[u8]) -> Cell {
+        String::from_utf8_lossy(bytes).into_owned().into()
+    }
+
+    /// The string, if this is a `Str` cell.
+    pub fn as_str(&self) -> Option<&str> {
+        match self {
+            Cell::Str(s) => Some(s),
+            Cell::Shared(s) => Some(s),
+            #[cfg(feature = "compact_str")]
+            Cell::Compact(s) => Some(s),
+            _ => None,
+        }
+    }
+
+    /// The value as an integer. `Str` cells are parsed.
+    pub fn as_i64(&self) -> Option<i64> {
+        match self {
+            Cell::Int(n) => Some(*n),
+            Cell::Str(s) => s.trim().parse().ok(),
+            Cell::Shared(s) => s.trim().parse().ok(),
+            #[cfg(feature = "compact_str")]
+            Cell::Compact(s) => s.trim().parse().ok(),
+            _ => None,
+        }
+    }
+
+    /// The value as a number. `Str` cells are parsed.
+    pub fn as_f64(&self) -> Option<f64> {
+        match self {
+            Cell::Int(n) => Some(*n as f64),
+            Cell::Float(n) => Some(*n),
+            Cell::Str(s) => s.trim().parse().ok(),
+            Cell::Shared(s) => s.trim().parse().ok(),
+            #[cfg(feature = "compact_str")]
+            Cell::Compact(s) => s.trim().parse().ok(),
+            _ => None,
+        }
+    }
+
+    /// The value as a boolean. `Str` cells are parsed.
+    pub fn as_bool(&self) -> Option<bool> {
+        match self {
+            Cell::Bool(b) => Some(*b),
+            Cell::Str(s) => s.trim().parse().ok(),
+            Cell::Shared(s) => s.trim().parse().ok(),
+            #[cfg(feature = "compact_str")]
+            Cell::Compact(s) => s.trim().parse().ok(),
+            _ => None,
+        }
+    }
+
+    /// Whether the cell renders as nothing.
+    pub fn is_empty(&self) -> bool {
+        match self {
+            Cell::Empty => true,
+            Cell::Str(s) => s.is_empty(),
+            Cell::Shared(s) => s.is_empty(),
+            #[cfg(feature = "compact_str")]
+            Cell::Compact(s) => s.is_empty(),
+            _ => false,
+        }
+    }
+
+    /// The rendered text of the cell. Only allocates for non-`Str` cells.
+    pub fn text(&self) -> Cow<'_, str> {
+        match self {
+            Cell::Str(s) => Cow::Borrowed(s),
+            Cell::Shared(s) => Cow::Borrowed(s),
+            #[cfg(feature = "compact_str")]
+            Cell::Compact(s) => Cow::Borrowed(s),
+            Cell::Empty => Cow::Borrowed(""),
+            other => Cow::Owned(other.to_string()),
+        }
+    }
+
+    fn rank(&self) -> u8 {
+        match self {
+            Cell::Int(_) | Cell::Float(_) => 0,
+            Cell::Bool(_) => 1,
+            Cell::Str(_) | Cell::Shared(_) => 2,
+            #[cfg(feature = "compact_str")]
+            Cell::Compact(_) => 2,
+            Cell::Empty => 3,
+        }
+    }
+
+    /// Bytes allocated for the cell's text. Shared text isn't counted, as the
+    /// table counts each interned string once.
+    pub(crate) fn heap_size(&self) -> usize {
+        match self {
+            Cell::Str(s) => s.capacity(),
+            #[cfg(feature = "compact_str")]
+            Cell::Compact(s) if s.is_heap_allocated() => s.capacity(),
+            _ => 0,
+        }
+    }
+
+    pub(crate) fn shrink_to_fit(&mut self) {
+        match self {
+            Cell::Str(s) => s.shrink_to_fit(),
+            #[cfg(feature = "compact_str")]
+            Cell::Compact(s) => s.shrink_to_fit(),
+            _ => {}
+        }
+    }
+}
+
+impl core::fmt::Display for Cell {
+    fn fmt(&self, f: &mut core::fmt::Formatter) -> core::fmt::Result {
+        match self {
+            Cell::Str(s) => f.write_str(s),
+            Cell::Shared(s) => f.write_str(s),
+            #[cfg(feature = "compact_str")]
+            Cell::Compact(s) => f.write_str(s),
+            Cell::Int(n) => write!(f, "{n}"),
+            Cell::Float(n) => write!(f, "{n}"),
+            Cell::Bool(b) => write!(f, "{b}"),
+            Cell::Empty => Ok(()),
+        }
+    }
+}
+
+/// Floats compare by bit pattern, so that `Cell` can be used as a map key.
+/// Text cells with the same text are equal, however they are stored.
+impl PartialEq for Cell {
+    fn eq(&self, other: &Self) -> bool {
+        match (self, other) {
+            _ if self.rank() == 2 && other.rank() == 2 => self.as_str() == other.as_str(),
+            (Cell::Int(a), Cell::Int(b)) => a == b,
+            (Cell::Float(a), Cell::Float(b)) => a.to_bits() == b.to_bits(),
+            (Cell::Bool(a), Cell::Bool(b)) => a == b,
+            (Cell::Empty, Cell::Empty) => true,
+            _ => false,
+        }
+    }
+}
+
+impl Eq for Cell {}
+
+/// Only text cells equal a string.
+impl PartialEq<str> for Cell {
+    fn eq(&self, other: &str) -> bool {
+        self.as_str() == Some(other)
+    }
+}
+
+impl PartialEq<&str> for Cell {
+    fn eq(&self, other: &&str) -> bool {
+        self.as_str() == Some(*other)
+    }
+}
+
+impl PartialEq<String> for Cell {
+    fn eq(&self, other: &String) -> bool {
+        self.as_str() == Some(other.as_str())
+    }
+}
+
+impl Hash for Cell {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.rank().hash(state);
+        match self {
+            Cell::Str(s) => s.hash(state),
+            Cell::Shared(s) => s.hash(state),
+            #[cfg(feature = "compact_str")]
+            Cell::Compact(s) => s.as_str().hash(state),
+            Cell::Int(n) => n.hash(state),
+            Cell::Float(n) => n.to_bits().hash(state),
+            Cell::Bool(b) => b.hash(state),
+            Cell::Empty => {}
+        }
+    }
+}
+
+/// Numbers sort before booleans, then strings, then empty cells. Integers and
+/// floats compare by value.
+impl Ord for Cell {
+    fn cmp(&self, other: &Self) -> Ordering {
+        match (self, other) {
+            _ if self.rank() == 2 && other.rank() == 2 => self.as_str().cmp(&other.as_str()),
+            (Cell::Int(a), Cell::Int(b)) => a.cmp(b),
+            (Cell::Bool(a), Cell::Bool(b)) => a.cmp(b),
+            (Cell::Int(_) | Cell::Float(_), Cell::Int(_) | Cell::Float(_)) => {
+                let (a, b) = (self.as_f64().unwrap_or_default(), other.as_f64().unwrap_or_default());
+                a.total_cmp(&b).then_with(|| matches!(self, Cell::Float(_)).cmp(&matches!(other, Cell::Float(_))))
+            }
+            _ => self.rank().cmp(&other.rank()),
+        }
+    }
+}
+
+impl PartialOrd for Cell {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl From<&str> for Cell {
+    #[cfg(not(feature = "compact_str"))]
+    fn from(s: &str) -> Cell {
+        Cell::Str(s.to_string())
+    }
+
+    #[cfg(feature = "compact_str")]
+    fn from(s: &str) -> Cell {
+        Cell::Compact(s.into())
+    }
+}
+
+impl From<String> for Cell {
+    #[cfg(not(feature = "compact_str"))]
+    fn from(s: String) -> Cell {
+        Cell::Str(s)
+    }
+
+    #[cfg(feature = "compact_str")]
+    fn from(s: String) -> Cell {
+        Cell::Compact(s.into())
+    }
+}
+
+impl From<&String> for Cell {
+    fn from(s: &String) -> Cell {
+        s.as_str().into()
+    }
+}
+
+impl From<bool> for Cell {
+    fn from(b: bool) -> Cell {
+        Cell::Bool(b)
+    }
+}
+
+impl From<f64> for Cell {
+    fn from(n: f64) -> Cell {
+        Cell::Float(n)
+    }
+}
+
+impl From<f32> for Cell {
+    /// Widen through the shortest decimal that reads back as `n`, so that
+    /// `0.1f32` is shown as `0.1` rather than `0.10000000149011612`.
+    fn from(n: f32) -> Cell {
+        Cell::Float(n.to_string().parse().unwrap_or(n as f64))
+    }
+}
+
+macro_rules! from_int {
+    ($($t:ty),*) => {
+        $(impl From<$t> for Cell {
+            fn from(n: $t) -> Cell {
+                Cell::Int(n.into())
+            }
+        })*
+    };
+}
+
+from_int!(i8, i16, i32, i64, u8, u16, u32);
+
+/// Values too large for an `i64` become floats.
+macro_rules! from_wide_int {
+    ($($t:ty),*) => {
+        $(impl From<$t> for Cell {
+            fn from(n: $t) -> Cell {
+                i64::try_from(n).map(Cell::Int).unwrap_or(Cell::Float(n as f64))
+            }
+        })*
+    };
+}
+
+from_wide_int!(u64, usize, isize);
+
+impl<T: Into<Cell>> From<Option<T>> for Cell {
+    fn from(value: Option<T>) -> Cell {
+        value.map(Into::into).unwrap_or(Cell::Empty)
+    }
+}
+
+/// Conversion for user types that should render the same way in every table.
+///
+/// ```
+/// use std::time::Duration;
+/// use tabular2::{Alignment, Cell, Row, Style, Table, ToCell};
+///
+/// struct Elapsed(Duration);
+///
+/// impl ToCell for Elapsed {
+///     fn to_cell(&self) -> Cell {
+///         format!("{:.1}s", self.0.as_secs_f64()).into()
+///     }
+///
+///     fn alignment(&self) -> Option<Alignment> {
+///         Some(Alignment::Right)
+///     }
+/// }
+///
+/// let table = Table::new()
+///     .header("Elapsed")
+///     .row(Row::new().value(&Elapsed(Duration::from_millis(1500))));
+/// assert_eq!(table.to_string(), "Elapsed  \n    1.5s \n");
+/// ```
+pub trait ToCell {
+    fn to_cell(&self) -> Cell;
+
+    /// Alignment for the column holding this value, used if the column
+    /// doesn't set one itself.
+    fn alignment(&self) -> Option<Alignment> {
+        None
+    }
+
+    /// Style for the rendered text. Styled cells are stored as text.
+    fn style(&self) -> Option<Style> {
+        None
+    }
+}
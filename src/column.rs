@@ -0,0 +1,170 @@
+use alloc::string::String;
+use alloc::sync::Arc;
+use core::cmp::Ordering;
+
+use crate::{Alignment, Cell, Header};
+
+/// A function applied to every cell of a column as it is added to the table.
+#[derive(Clone)]
+pub struct CellFormat(Arc<dyn Fn(&str) -> String + Send + Sync>);
+
+impl CellFormat {
+    pub fn new<F: Fn(&str) -> String + Send + Sync + 'static>(f: F) -> Self {
+        CellFormat(Arc::new(f))
+    }
+
+    pub fn apply(&self, value: &str) -> String {
+        (self.0)(value)
+    }
+
+    /// Format the text of `cell`. The result is always a text cell.
+    pub fn apply_cell(&self, cell: &Cell) -> Cell {
+        self.apply(&cell.text()).into()
+    }
+}
+
+impl core::fmt::Debug for CellFormat {
+    fn fmt(&self, f: &mut core::fmt::Formatter) -> core::fmt::Result {
+        f.write_str("CellFormat(..)")
+    }
+}
+
+/// Two formats are equal only if they are the same function.
+impl PartialEq for CellFormat {
+    fn eq(&self, other: &Self) -> bool {
+        Arc::ptr_eq(&self.0, &other.0)
+    }
+}
+
+impl Eq for CellFormat {}
+
+/// The kind of values a column holds. Numeric columns right-align their cells
+/// by default, and sorting compares cells by value rather than as text.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum ColumnType {
+    Str,
+    Int,
+    Float,
+    Bool,
+}
+
+impl ColumnType {
+    /// Compare two cells as values of this type. `Str` cells are parsed, and
+    /// cells that aren't values of the type sort after those that are.
+    pub fn compare(&self, a: &Cell, b: &Cell) -> Ordering {
+        fn by<V: PartialOrd>(a: &Cell, b: &Cell, value: impl Fn(&Cell) -> Option<V>) -> Ordering {
+            match (value(a), value(b)) {
+                (Some(x), Some(y)) => x.partial_cmp(&y).unwrap_or(Ordering::Equal),
+                (Some(_), None) => Ordering::Less,
+                (None, Some(_)) => Ordering::Greater,
+                (None, None) => a.cmp(b),
+            }
+        }
+        match self {
+            ColumnType::Str => a.text().cmp(&b.text()),
+            ColumnType::Int => by(a, b, Cell::as_i64),
+            ColumnType::Float => by(a, b, Cell::as_f64),
+            ColumnType::Bool => by(a, b, Cell::as_bool),
+        }
+    }
+
+    /// The alignment used for data cells when the column doesn't set one.
+    pub fn default_alignment(&self) -> Alignment {
+        match self {
+            ColumnType::Int | ColumnType::Float => Alignment::Right,
+            ColumnType::Str | ColumnType::Bool => Alignment::Left,
+        }
+    }
+}
+
+/// Builder for a column definition, accepted anywhere a [`Header`] is.
+///
+/// ```
+/// use tabular2::{Alignment, Column, Row, Table};
+///
+/// let table = Table::new()
+///     .header("Item")
+///     .header(Column::new("Price").align(Alignment::Right).format(|v| format!("${v}")))
+///     .row(Row::new().cell("Coffee").cell("3.50"));
+/// assert_eq!(table.to_string(), "Item        Price \nCoffee      $3.50 \n");
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Column {
+    header: Header,
+}
+
+impl Column {
+    pub fn new(text: &str) -> Self {
+        Column {
+            header: text.into(),
+        }
+    }
+
+    /// Align both the header and the data cells of this column.
+    pub fn align(mut self, alignment: Alignment) -> Self {
+        self.header.alignment = alignment;
+        self.header.cell_alignment = Some(alignment);
+        self
+    }
+
+    /// Never render the column narrower than `width`.
+    pub fn min_width(mut self, width: usize) -> Self {
+        self.header.min_width = Some(width);
+        self
+    }
+
+    /// Never render the column wider than `width`. Longer cells are truncated
+    /// with an ellipsis.
+    pub fn max_width(mut self, width: usize) -> Self {
+        self.header.max_width = Some(width);
+        self
+    }
+
+    /// Keep this column over those with a lower priority when the table
+    /// doesn't fit its [`fit_width`](crate::Table::fit_width). Columns have
+    /// priority 0 unless set.
+    pub fn priority(mut self, priority: u32) -> Self {
+        self.header.priority = Some(priority);
+        self
+    }
+
+    /// Take `weight` times the usual share of the truncation when the table
+    /// is narrowed to fit its [`fit_width`](crate::Table::fit_width), so that
+    /// verbose columns give up space before important ones. Columns have
+    /// weight 1 unless set, and a column of weight 0 keeps its width unless
+    /// the others can't narrow enough.
+    pub fn shrink_weight(mut self, weight: u32) -> Self {
+        self.header.shrink_weight = Some(weight);
+        self
+    }
+
+    /// Declare the kind of values this column holds.
+    pub fn kind(mut self, kind: ColumnType) -> Self {
+        self.header.column_type = Some(kind);
+        self
+    }
+
+    /// Transform the text of the cells of this column as they are shown in
+    /// text layouts: plain, Markdown and CSV. The cells keep their values, so
+    /// sorting, totals and JSON see the values rather than the text. Empty
+    /// cells aren't formatted and show the [null text](crate::Table::null_text).
+    pub fn format<F: Fn(&str) -> String + Send + Sync + 'static>(mut self, f: F) -> Self {
+        self.header.format = Some(CellFormat::new(f));
+        self
+    }
+
+    /// Store one copy of each distinct text in this column, shared by every
+    /// cell holding it. Saves memory for columns with few distinct values,
+    /// such as a status or region.
+    pub fn intern(mut self) -> Self {
+        self.header.intern = true;
+        self
+    }
+}
+
+impl From<Column> for Header {
+    fn from(column: Column) -> Header {
+        column.header
+    }
+}
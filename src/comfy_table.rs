@@ -0,0 +1,38 @@
+use crate::{Cell, Config, Header, ModifyRows, Table};
+
+/// Copies the header and cell contents. Styling and column constraints are not
+/// carried over.
+impl<const N: usize> From<&comfy_table::Table> for Table<ModifyRows, N> {
+    fn from(table: &comfy_table::Table) -> Self {
+        let headers: Vec<Header> = match table.header() {
+            Some(header) => header.cell_iter().map(|cell| cell.content().into()).collect(),
+            None => Vec::new(),
+        };
+        let rows = table.row_iter()
+            .map(|row| row.cell_iter().map(|cell| Cell::from(cell.content())).collect())
+            .collect();
+        Table::from_parts(headers, rows, Config::default())
+    }
+}
+
+impl<const N: usize> From<comfy_table::Table> for Table<ModifyRows, N> {
+    fn from(table: comfy_table::Table) -> Self {
+        Table::from(&table)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{ModifyRows, Table};
+
+    #[test]
+    fn test_from_comfy_table() {
+        let mut source = comfy_table::Table::new();
+        source.set_header(vec!["Host", "State"]);
+        source.add_row(vec!["web-1", "up"]);
+        let table: Table<ModifyRows> = Table::from(source);
+        assert_eq!(table.to_string(),
+                   "Host     State    \n".to_owned() +
+                       "web-1    up       \n");
+    }
+}
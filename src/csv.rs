@@ -0,0 +1,77 @@
+use std::io;
+
+use crate::{Cell, Config, Header, ModifyRows, Row, StreamingTable, Table};
+
+impl<const N: usize> Table<ModifyRows, N> {
+    /// Read a table from CSV. With `has_headers`, the first record becomes the
+    /// headers; otherwise the table has none. Every cell is stored as text.
+    pub fn from_csv_reader<R: io::Read>(reader: R, has_headers: bool) -> Result<Self, csv::Error> {
+        let mut reader = csv::ReaderBuilder::new()
+            .has_headers(has_headers)
+            .from_reader(reader);
+        let headers: Vec<Header> = if has_headers {
+            reader.headers()?.iter().map(Into::into).collect()
+        } else {
+            Vec::new()
+        };
+        let rows = reader.records()
+            .map(|record| record.map(|record| record.iter().map(Cell::from).collect()))
+            .collect::<Result<Vec<Vec<Cell>>, _>>()?;
+        Ok(Table::from_parts(headers, rows, Config::default()))
+    }
+}
+
+/// Copy CSV from `reader` to `writer` as aligned columns, without holding
+/// the whole file. The first `sample` records are buffered to size the
+/// columns; later cells that don't fit are truncated. With `has_headers`, the
+/// first record is the header line.
+pub fn align_csv<R: io::Read, W: io::Write>(reader: R, writer: W, has_headers: bool, sample: usize) -> Result<(), csv::Error> {
+    let mut reader = csv::ReaderBuilder::new()
+        .has_headers(has_headers)
+        .from_reader(reader);
+    let mut header = Table::new();
+    if has_headers {
+        for field in reader.headers()?.iter() {
+            header = header.header(field);
+        }
+    }
+    let mut out = StreamingTable::adaptive(header, sample, writer);
+    for record in reader.records() {
+        out.push_row(record?.iter().fold(Row::new(), Row::cell))?;
+    }
+    out.finish()?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{align_csv, ModifyRows, Table};
+
+    #[test]
+    fn test_from_csv_reader() {
+        let data = "name,region\nweb-1,us-east\ndb-1,eu-west\n";
+        let table: Table<ModifyRows> = Table::from_csv_reader(data.as_bytes(), true).unwrap();
+        assert_eq!(table.to_string(),
+                   "name     region   \n".to_owned() +
+                       "web-1    us-east  \n" +
+                       "db-1     eu-west  \n");
+
+        let table: Table<ModifyRows> = Table::from_csv_reader(data.as_bytes(), false).unwrap();
+        assert_eq!(table.row_count(), 3);
+        assert!(table.headers().is_empty());
+
+        assert!(Table::<ModifyRows>::from_csv_reader("a,b\n1\n".as_bytes(), true).is_err());
+    }
+
+    #[test]
+    fn test_align_csv() {
+        let data = "name,path\na,/srv\nb,/srv/data/archive\n";
+        let mut out = Vec::new();
+        align_csv(data.as_bytes(), &mut out, true, 1).unwrap();
+        assert_eq!(String::from_utf8(out).unwrap(),
+                   "name     path     \n".to_owned() +
+                       "a        /srv     \n" +
+                       "b        /srv/da… \n");
+        assert!(align_csv("a,b\n1\n".as_bytes(), Vec::new(), true, 10).is_err());
+    }
+}
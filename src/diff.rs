@@ -0,0 +1,120 @@
+use alloc::collections::BTreeMap;
+use alloc::format;
+use alloc::vec::Vec;
+
+use crate::{cell, Cell, Header, ModifyRows, Table};
+
+impl<T, const N: usize> Table<T, N> {
+    /// Compare this table (before) against `other` (after). Rows are matched by their first cell.
+    /// Rows that share a key are matched in order, the first with the first, so a key repeated
+    /// more times on one side shows its extra rows as added or removed.
+    ///
+    /// The result has a leading marker column: `+` for rows only in `other`, `-` for rows only in
+    /// `self`, `~` for rows with changed cells, and blank for unchanged rows. Changed cells are
+    /// shown as `old -> new`. Rows of `self` keep their order, and added rows come last.
+    pub fn diff<U>(&self, other: &Table<U, N>) -> Table<ModifyRows, N> {
+        let mut after: BTreeMap<&Cell, Vec<&[Cell]>> = BTreeMap::new();
+        for row in other.rows.iter() {
+            after.entry(cell(row, 0)).or_default().push(row);
+        }
+        let mut before: BTreeMap<&Cell, usize> = BTreeMap::new();
+
+        let mut rows = Vec::with_capacity(self.rows.len());
+        for old in self.rows.iter() {
+            let seen = before.entry(cell(old, 0)).or_default();
+            let matched = after.get(cell(old, 0)).and_then(|rows| rows.get(*seen));
+            *seen += 1;
+            match matched {
+                None => rows.push(marked("-", old.iter().enumerate().map(|(i, c)| self.shown_cell(i, c)))),
+                Some(new) => {
+                    let ncols = core::cmp::max(old.len(), new.len());
+                    let mut changed = false;
+                    let cells: Vec<Cell> = (0..ncols)
+                        .map(|i| {
+                            let (a, b) = (cell(old, i), cell(new, i));
+                            if a == b {
+                                self.shown_cell(i, a)
+                            } else {
+                                changed = true;
+                                format!("{} -> {}", self.shown_text(i, a), other.shown_text(i, b)).into()
+                            }
+                        })
+                        .collect();
+                    rows.push(marked(if changed { "~" } else { "" }, cells.into_iter()));
+                }
+            }
+        }
+        let mut seen: BTreeMap<&Cell, usize> = BTreeMap::new();
+        for new in other.rows.iter() {
+            let seen = seen.entry(cell(new, 0)).or_default();
+            if *seen >= before.get(cell(new, 0)).copied().unwrap_or(0) {
+                rows.push(marked("+", new.iter().enumerate().map(|(i, c)| other.shown_cell(i, c))));
+            }
+            *seen += 1;
+        }
+
+        let mut headers = Vec::with_capacity(self.headers.len() + 1);
+        headers.push(Header::default());
+        // The cells are already formatted.
+        headers.extend(self.headers.iter().map(|h| Header { format: None, ..h.clone() }));
+        Table::from_parts(headers, rows, self.config.clone())
+    }
+}
+
+fn marked(marker: &str, cells: impl Iterator<Item = Cell>) -> Vec<Cell> {
+    core::iter::once(marker.into()).chain(cells).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{ModifyRows, Row, Table};
+
+    #[test]
+    fn test_diff() {
+        let before: Table<ModifyRows> = Table::new()
+            .header("Key")
+            .header("Value")
+            .row(Row::new().cell("timeout").cell("30"))
+            .row(Row::new().cell("retries").cell("3"))
+            .row(Row::new().cell("debug").cell("false"));
+        let after: Table<ModifyRows> = Table::new()
+            .header("Key")
+            .header("Value")
+            .row(Row::new().cell("timeout").cell("60"))
+            .row(Row::new().cell("retries").cell("3"))
+            .row(Row::new().cell("verbose").cell("true"));
+        let diff = before.diff(&after);
+        assert_eq!(crate::tests::texts(&diff), vec![
+            vec!["~", "timeout", "30 -> 60"],
+            vec!["", "retries", "3"],
+            vec!["-", "debug", "false"],
+            vec!["+", "verbose", "true"],
+        ]);
+    }
+
+    #[test]
+    fn test_diff_duplicate_keys() {
+        let before: Table<ModifyRows> = Table::new()
+            .header("Host")
+            .header("Port")
+            .row(Row::new().cell("web").cell("80"))
+            .row(Row::new().cell("web").cell("443"))
+            .row(Row::new().cell("db").cell("5432"))
+            .row(Row::new().cell("db").cell("5433"));
+        let after: Table<ModifyRows> = Table::new()
+            .header("Host")
+            .header("Port")
+            .row(Row::new().cell("web").cell("80"))
+            .row(Row::new().cell("web").cell("8443"))
+            .row(Row::new().cell("web").cell("8080"))
+            .row(Row::new().cell("db").cell("5432"));
+        let diff = before.diff(&after);
+        assert_eq!(crate::tests::texts(&diff), vec![
+            vec!["", "web", "80"],
+            vec!["~", "web", "443 -> 8443"],
+            vec!["", "db", "5432"],
+            vec!["-", "db", "5433"],
+            vec!["+", "web", "8080"],
+        ]);
+    }
+}
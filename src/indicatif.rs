@@ -0,0 +1,131 @@
+use std::io;
+
+use indicatif::MultiProgress;
+
+use crate::Table;
+
+impl<T, const N: usize> Table<T, N> {
+    /// Print rows from `from_row` onwards above the progress bars of `bars`,
+    /// and return the index to pass on the next call. The header is printed
+    /// with the first row.
+    ///
+    /// Lines already printed can't be realigned, so give columns a
+    /// [`min_width`](crate::Column::min_width) wide enough for later rows.
+    pub fn print_above(&self, bars: &MultiProgress, from_row: usize) -> io::Result<usize> {
+        let widths = self.display_widths();
+        let mut line = String::new();
+        if from_row == 0 && !self.config.skip_header && !self.headers.is_empty() {
+            self.write_plain_header(&mut line, &widths).expect("writing to a String can't fail");
+            bars.println(line.trim_end_matches('\n'))?;
+        }
+        for (row, meta) in self.rows.iter().zip(self.meta.iter()).skip(from_row) {
+            line.clear();
+            self.write_plain_row(&mut line, row, &meta.widths, &widths).expect("writing to a String can't fail");
+            bars.println(line.trim_end_matches('\n'))?;
+        }
+        Ok(self.rows.len())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io;
+    use std::sync::{Arc, Mutex};
+
+    use indicatif::{MultiProgress, ProgressDrawTarget, TermLike};
+
+    use crate::{ModifyRows, Row, Table};
+
+    const WIDTH: usize = 80;
+
+    /// A terminal screen in memory. Lines wrap at [`WIDTH`] columns, as
+    /// indicatif expects when it pads them to the terminal width.
+    #[derive(Debug, Clone, Default)]
+    struct Screen(Arc<Mutex<(Vec<String>, usize)>>);
+
+    impl Screen {
+        /// The lines on the screen, without trailing spaces.
+        fn lines(&self) -> Vec<String> {
+            let (lines, _) = &*self.0.lock().unwrap();
+            lines.iter()
+                .map(|line| line.trim_end().to_string())
+                .filter(|line| !line.is_empty())
+                .collect()
+        }
+    }
+
+    impl TermLike for Screen {
+        fn width(&self) -> u16 {
+            WIDTH as u16
+        }
+
+        fn move_cursor_up(&self, n: usize) -> io::Result<()> {
+            let (_, row) = &mut *self.0.lock().unwrap();
+            *row = row.saturating_sub(n);
+            Ok(())
+        }
+
+        fn move_cursor_down(&self, n: usize) -> io::Result<()> {
+            self.0.lock().unwrap().1 += n;
+            Ok(())
+        }
+
+        fn move_cursor_right(&self, _: usize) -> io::Result<()> {
+            Ok(())
+        }
+
+        fn move_cursor_left(&self, _: usize) -> io::Result<()> {
+            Ok(())
+        }
+
+        fn write_line(&self, s: &str) -> io::Result<()> {
+            self.write_str(s)?;
+            self.0.lock().unwrap().1 += 1;
+            Ok(())
+        }
+
+        fn write_str(&self, s: &str) -> io::Result<()> {
+            let (lines, row) = &mut *self.0.lock().unwrap();
+            for c in s.chars().filter(|&c| c != '\r') {
+                if lines.len() <= *row {
+                    lines.resize(*row + 1, String::new());
+                }
+                lines[*row].push(c);
+                if lines[*row].chars().count() == WIDTH {
+                    *row += 1;
+                }
+            }
+            Ok(())
+        }
+
+        fn clear_line(&self) -> io::Result<()> {
+            let (lines, row) = &mut *self.0.lock().unwrap();
+            if let Some(line) = lines.get_mut(*row) {
+                line.clear();
+            }
+            Ok(())
+        }
+
+        fn flush(&self) -> io::Result<()> {
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn test_print_above() {
+        let screen = Screen::default();
+        let bars = MultiProgress::with_draw_target(ProgressDrawTarget::term_like(Box::new(screen.clone())));
+        let mut table: Table<ModifyRows> = Table::new().header("File").header("Size").end_header();
+        table.extend([Row::new().cell("a.txt").cell(12)]);
+        let next = table.print_above(&bars, 0).unwrap();
+        table.extend([Row::new().cell("b.txt").cell(3), Row::new().cell("c.txt").cell(40)]);
+        assert_eq!(table.print_above(&bars, next).unwrap(), 3);
+        assert_eq!(screen.lines(), vec![
+            "File     Size",
+            "a.txt    12",
+            "b.txt    3",
+            "c.txt    40",
+        ]);
+        assert_eq!(screen.lines(), table.lines().map(|line| line.trim_end().to_string()).collect::<Vec<_>>());
+    }
+}
@@ -0,0 +1,58 @@
+use serde_json::Value;
+
+use crate::record::union_of_fields;
+use crate::{Cell, Config, ModifyRows, RecordError, Table};
+
+impl<const N: usize> Table<ModifyRows, N> {
+    /// Build a table from a JSON array of objects. Headers are the union of the keys, in the order
+    /// they are first seen, and objects missing a key get an empty cell. Nested arrays and objects
+    /// are shown as JSON text.
+    pub fn from_json_array(value: &Value) -> Result<Self, RecordError> {
+        let Value::Array(items) = value else {
+            return Err(RecordError("expected a JSON array".to_string()));
+        };
+        let records = items.iter()
+            .map(|item| match item {
+                Value::Object(object) => Ok(object.iter().map(|(k, v)| (k.clone(), json_cell(v))).collect()),
+                _ => Err(RecordError(format!("expected a JSON object, found `{item}`"))),
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        let (headers, rows) = union_of_fields(records);
+        Ok(Table::from_parts(headers, rows, Config::default()))
+    }
+}
+
+fn json_cell(value: &Value) -> Cell {
+    match value {
+        Value::Null => Cell::Empty,
+        Value::Bool(b) => (*b).into(),
+        Value::Number(n) => match n.as_i64() {
+            Some(i) => i.into(),
+            None => n.as_f64().into(),
+        },
+        Value::String(s) => s.as_str().into(),
+        Value::Array(_) | Value::Object(_) => value.to_string().into(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use crate::{tests::texts, Cell, ModifyRows, Table};
+
+    #[test]
+    fn test_from_json_array() {
+        let value = json!([
+            {"id": 1, "name": "web", "tags": ["prod"]},
+            {"id": 2, "up": false},
+        ]);
+        let table: Table<ModifyRows> = Table::from_json_array(&value).unwrap();
+        assert_eq!(table.headers().iter().map(|h| h.text.as_str()).collect::<Vec<_>>(), vec!["id", "name", "tags", "up"]);
+        assert_eq!(texts(&table), vec![vec!["1", "web", "[\"prod\"]", ""], vec!["2", "", "", "false"]]);
+        assert_eq!(table.get(1, 0), Some(&Cell::Int(2)));
+
+        assert!(Table::<ModifyRows>::from_json_array(&json!({"id": 1})).is_err());
+        assert!(Table::<ModifyRows>::from_json_array(&json!([1, 2])).is_err());
+    }
+}
@@ -1,179 +1,3389 @@
-use std::marker::PhantomData;
+//! Without the default `std` feature, the crate builds under `no_std` with
+//! `alloc`: tables can be built, edited and rendered to any `fmt::Write`.
+//! Writing to `io::Write`, [`StreamingTable`] and the integrations need `std`.
+//!
+//! The default `ansi` and `unicode-width` features measure text with the
+//! `strip-ansi-escapes` and `unicode-width` crates. Without them the crate
+//! strips escape sequences itself and counts every character as one column,
+//! so `default-features = false, features = ["std"]` has no dependencies.
+//!
+//! The default features have no platform-specific dependencies, so the crate
+//! builds for `wasm32-unknown-unknown`. [`render`](Table::render) and
+//! [`render_to`](Table::render_to) give the output as text, for sending from a
+//! web server or playground.
+#![cfg_attr(not(any(feature = "std", test)), no_std)]
 
+extern crate alloc;
+
+use alloc::borrow::Cow;
+use alloc::format;
+use alloc::string::{String, ToString};
+use alloc::sync::Arc;
+use alloc::vec;
+use alloc::vec::Vec;
+use core::any::Any;
+use core::marker::PhantomData;
+
+// Lets the paths generated by `#[derive(Tabular)]` resolve inside this crate too.
+extern crate self as tabular2;
+
+mod aggregate;
+mod ansi;
+#[cfg(feature = "arrow")]
+mod arrow;
+mod borrowed;
+#[cfg(feature = "bumpalo")]
+mod bumpalo;
+mod case;
+mod cell;
+mod column;
+#[cfg(feature = "comfy-table")]
+mod comfy_table;
+#[cfg(feature = "csv")]
+mod csv;
+mod diff;
+#[cfg(feature = "indicatif")]
+mod indicatif;
+#[cfg(feature = "json")]
+mod json;
+mod live;
+#[cfg(feature = "locale")]
+mod locale;
+#[cfg(feature = "log")]
+mod log;
+mod output;
+mod parse;
+mod plain;
+#[cfg(feature = "polars")]
+mod polars;
+#[cfg(feature = "tokio-postgres")]
+mod postgres;
+#[cfg(feature = "prettytable")]
+mod prettytable;
+#[cfg(feature = "ratatui")]
+mod ratatui;
+#[cfg(feature = "rayon")]
+mod rayon;
+mod record;
+mod rows;
+#[cfg(feature = "serde")]
+mod record_serializer;
+#[cfg(feature = "rusqlite")]
+mod rusqlite;
+#[cfg(feature = "serde")]
+mod serialize;
+#[cfg(feature = "sqlx")]
+mod sqlx;
+#[cfg(feature = "std")]
+mod streaming;
+mod style;
+#[cfg(feature = "tokio")]
+mod tokio;
+#[cfg(feature = "tracing")]
+mod tracing;
+
+pub use aggregate::{Aggregation, ColumnStats};
+pub use borrowed::BorrowedTable;
+pub use case::HeaderCase;
+pub use cell::{Cell, ToCell};
+pub use column::{CellFormat, Column, ColumnType};
+#[cfg(feature = "csv")]
+pub use self::csv::align_csv;
+pub use live::LiveRenderer;
+#[cfg(feature = "locale")]
+pub use locale::{DateLocale, NumberLocale};
+pub use output::{CsvOptions, CsvQuoting, LineEnding, OutputFormat, ParseOutputFormatError};
+pub use parse::ParseMarkdownError;
+pub use record::Tabular;
+#[cfg(feature = "serde")]
+pub use record_serializer::RecordError;
+#[cfg(feature = "derive")]
+pub use tabular2_derive::Tabular;
+#[cfg(feature = "std")]
+pub use streaming::StreamingTable;
+pub use style::{Color, Style};
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
 pub struct ModifyHeader;
 
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
 pub struct ModifyRows;
 
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub struct Table<T = ModifyHeader, const N: usize = 0> {
     headers: Vec<Header>,
     column_widths: Vec<usize>,
-    rows: Vec<Vec<String>>,
-    skip_header: bool,
+    /// Number of empty cells in each column, as the null text widens the
+    /// columns that show it.
+    empty_cells: Vec<usize>,
+    rows: rows::Rows,
+    /// One entry per row.
+    meta: Vec<RowMeta>,
+    /// Text of the cells in interned columns, one copy per distinct value.
+    interned: alloc::collections::BTreeSet<Arc<str>>,
+    config: Config,
+    /// Progress of [`render_new_rows`](Table::render_new_rows).
+    printed: Option<Printed>,
     _pd: PhantomData<T>,
 }
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+/// Metadata attached to a row with [`Row::tag`]. It isn't rendered.
+#[derive(Clone)]
+struct RowTag(Arc<dyn Any + Send + Sync>);
+
+impl core::fmt::Debug for RowTag {
+    fn fmt(&self, f: &mut core::fmt::Formatter) -> core::fmt::Result {
+        f.write_str("RowTag(..)")
+    }
+}
+
+/// Two tags are equal only if they are the same value.
+impl PartialEq for RowTag {
+    fn eq(&self, other: &Self) -> bool {
+        Arc::ptr_eq(&self.0, &other.0)
+    }
+}
+
+impl Eq for RowTag {}
+
+/// What a table keeps alongside the cells of each row.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+struct RowMeta {
+    /// Visible width of each cell, measured when it was stored so that
+    /// rendering doesn't have to.
+    widths: Vec<usize>,
+    tag: Option<RowTag>,
+}
+
+impl RowMeta {
+    /// Measure `cells` as they are shown under `headers`.
+    fn measure(headers: &[Header], cells: &[Cell], strategy: WidthStrategy) -> Self {
+        RowMeta {
+            widths: cells.iter()
+                .enumerate()
+                .map(|(i, cell)| shown_width(headers.get(i), cell, strategy))
+                .collect(),
+            tag: None,
+        }
+    }
+}
+
+/// How much of a table has been rendered incrementally, and at what widths.
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Printed {
+    rows: usize,
+    widths: Vec<usize>,
+}
+
+/// Settings that don't depend on the contents of the table.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+struct Config {
+    skip_header: bool,
+    row_length_policy: RowLengthPolicy,
+    /// Shown in place of empty cells.
+    null_text: String,
+    /// Printed under the header when there are no rows.
+    empty_message: Option<String>,
+    /// Column count enforced on a table without headers. `Some(0)` takes the
+    /// count from the first row.
+    headerless_columns: Option<usize>,
+    /// Rows rendered before the rest are left out.
+    max_render_rows: Option<usize>,
+    /// Widths set with [`fixed_widths`](Table::fixed_widths). Column widths
+    /// aren't tracked while this is set.
+    fixed_widths: Option<Vec<usize>>,
+    invalid_text: InvalidTextPolicy,
+    newlines: NewlinePolicy,
+    ansi: AnsiPolicy,
+    /// Alignment of data cells in columns that don't set one.
+    default_alignment: Alignment,
+    empty_table: EmptyTablePolicy,
+    csv: output::CsvOptions,
+    /// Width the rendered lines should fit in, and how to make them.
+    fit_width: Option<(usize, OverflowPolicy)>,
+    width_strategy: WidthStrategy,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum Alignment {
+    #[default]
     Left,
     Right,
     Center,
 }
 
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(default))]
 pub struct Header {
     pub text: String,
+    /// Alignment of the header text.
     pub alignment: Alignment,
+    /// Alignment of the data cells. Defaults to the column type's alignment,
+    /// or left.
+    #[cfg_attr(feature = "serde", serde(skip_serializing_if = "Option::is_none"))]
+    pub cell_alignment: Option<Alignment>,
+    #[cfg_attr(feature = "serde", serde(skip_serializing_if = "Option::is_none"))]
+    pub column_type: Option<ColumnType>,
+    #[cfg_attr(feature = "serde", serde(skip_serializing_if = "Option::is_none"))]
+    pub min_width: Option<usize>,
+    #[cfg_attr(feature = "serde", serde(skip_serializing_if = "Option::is_none"))]
+    pub max_width: Option<usize>,
+    #[cfg_attr(feature = "serde", serde(skip))]
+    pub format: Option<CellFormat>,
+    /// How important the column is when the table doesn't fit its width.
+    /// See [`Column::priority`].
+    #[cfg_attr(feature = "serde", serde(skip_serializing_if = "Option::is_none"))]
+    pub priority: Option<u32>,
+    /// How much of the truncation this column takes when the table is
+    /// narrowed to fit its width. See [`Column::shrink_weight`].
+    #[cfg_attr(feature = "serde", serde(skip_serializing_if = "Option::is_none"))]
+    pub shrink_weight: Option<u32>,
+    /// Store one copy of each distinct text in this column. See [`Column::intern`].
+    #[cfg_attr(feature = "serde", serde(skip_serializing_if = "core::ops::Not::not"))]
+    pub intern: bool,
+}
+
+impl From<&str> for Header {
+    fn from(text: &str) -> Header {
+        text.to_string().into()
+    }
 }
 
-impl Into<Header> for &str {
-    fn into(self) -> Header {
+impl From<String> for Header {
+    fn from(text: String) -> Header {
         Header {
-            text: self.to_string(),
-            alignment: Alignment::Left,
+            text,
+            ..Header::default()
+        }
+    }
+}
+
+/// Columns are never rendered narrower than this unless a header sets its own
+/// `min_width`.
+const DEFAULT_MIN_WIDTH: usize = 8;
+
+/// Direction of a sort.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum SortOrder {
+    #[default]
+    Ascending,
+    Descending,
+}
+
+/// What to do when a table is wider than the width set with
+/// [`fit_width`](Table::fit_width).
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum OverflowPolicy {
+    /// Leave out the columns with the lowest [`priority`](Column::priority),
+    /// the rightmost first among equals, until the rest fit.
+    DropColumns,
+    /// Print the columns that fit, then the rest below them as further
+    /// tables, each with its own header.
+    Wrap,
+    /// Narrow the columns, truncating their cells. Each column gives up space
+    /// in proportion to its width times its
+    /// [`shrink_weight`](Column::shrink_weight).
+    #[default]
+    Truncate,
+}
+
+/// How a table with no rows is rendered.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum EmptyTablePolicy {
+    /// Print the header, followed by the [`empty_message`](Table::empty_message)
+    /// if one is set.
+    #[default]
+    Header,
+    /// Print the header and, centered under it, the empty message or
+    /// "(no data)".
+    Placeholder,
+    /// Print nothing at all.
+    Nothing,
+}
+
+/// What to do with a row whose length differs from the number of headers.
+/// Tables without headers take their shape from their rows and ignore the policy.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum RowLengthPolicy {
+    /// Reject the row. Infallible methods like `row` panic, so use `try_row`
+    /// or `try_push_row` to handle the error.
+    Error,
+    /// Pad short rows with empty cells. Long rows are rejected as with `Error`.
+    PadWithEmpty,
+    /// Pad short rows with empty cells and drop the extra cells of long rows.
+    #[default]
+    Truncate,
+    /// Pad short rows with empty cells, and add empty headers to make room for
+    /// the extra cells of long rows.
+    GrowHeaders,
+}
+
+/// What to do with U+FFFD replacement characters in text cells. They stand
+/// for bytes that weren't valid UTF-8, as left by [`Cell::from_utf8_lossy`].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum InvalidTextPolicy {
+    /// Store and show them as they are. Each is one column wide.
+    #[default]
+    Keep,
+    /// Drop them from the text.
+    Remove,
+    /// Replace each with another character, such as `?`.
+    Substitute(char),
+}
+
+impl InvalidTextPolicy {
+    fn apply(self, cell: Cell) -> Cell {
+        let mut buf = [0; 4];
+        let with = match self {
+            InvalidTextPolicy::Keep => return cell,
+            InvalidTextPolicy::Remove => "",
+            InvalidTextPolicy::Substitute(c) => c.encode_utf8(&mut buf),
+        };
+        match cell.as_str() {
+            Some(text) if text.contains(char::REPLACEMENT_CHARACTER) => Cell::Str(text.replace(char::REPLACEMENT_CHARACTER, with)),
+            _ => cell,
+        }
+    }
+}
+
+/// What to do with line breaks in text cells, which would otherwise split a
+/// row across lines and break the layout.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum NewlinePolicy {
+    /// Store them as they are.
+    #[default]
+    Keep,
+    /// Replace them with `\n` and `\r`, as written in Rust source.
+    Escape,
+    /// Replace each line break, including `\r\n`, with a space.
+    Space,
+}
+
+impl NewlinePolicy {
+    fn apply(self, cell: Cell) -> Cell {
+        if self == NewlinePolicy::Keep {
+            return cell;
+        }
+        let Some(text) = cell.as_str().filter(|text| text.contains(['\n', '\r'])) else { return cell };
+        Cell::Str(self.replace(text))
+    }
+
+    /// `text` with its line breaks replaced as `Escape` or `Space` would.
+    fn replace(self, text: &str) -> String {
+        match self {
+            NewlinePolicy::Keep => text.to_string(),
+            NewlinePolicy::Escape => text.replace('\r', "\\r").replace('\n', "\\n"),
+            NewlinePolicy::Space => text.replace("\r\n", " ").replace(['\n', '\r'], " "),
+        }
+    }
+}
+
+/// How escape sequences in text cells, such as colors, are treated. Column
+/// widths always count only the visible text, and CSV and JSON output never
+/// contains escape sequences.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum AnsiPolicy {
+    /// Store them and render them in aligned output.
+    #[default]
+    KeepAndMeasureVisible,
+    /// Remove them from cells as they are added.
+    StripBeforeStore,
+    /// Treat them as errors: [`Table::try_render`] fails on a cell that has
+    /// one, and other rendering leaves them out.
+    Reject,
+}
+
+/// Identifies a column, either by position or by header text.
+pub trait ColumnIndex {
+    fn index_in(&self, headers: &[Header]) -> Option<usize>;
+}
+
+impl ColumnIndex for usize {
+    fn index_in(&self, headers: &[Header]) -> Option<usize> {
+        (*self < headers.len()).then_some(*self)
+    }
+}
+
+impl ColumnIndex for &str {
+    fn index_in(&self, headers: &[Header]) -> Option<usize> {
+        headers.iter().position(|h| h.text == *self)
+    }
+}
+
+impl ColumnIndex for String {
+    fn index_in(&self, headers: &[Header]) -> Option<usize> {
+        self.as_str().index_in(headers)
+    }
+}
+
+/// Returned when a column of values doesn't have one value per row.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ColumnLengthError {
+    pub expected: usize,
+    pub actual: usize,
+}
+
+impl core::fmt::Display for ColumnLengthError {
+    fn fmt(&self, f: &mut core::fmt::Formatter) -> core::fmt::Result {
+        write!(f, "column has {} values, but the table has {} rows", self.actual, self.expected)
+    }
+}
+
+impl core::error::Error for ColumnLengthError {}
+
+/// Returned when a row doesn't have one cell per column.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ArityError {
+    pub expected: usize,
+    pub actual: usize,
+}
+
+impl core::fmt::Display for ArityError {
+    fn fmt(&self, f: &mut core::fmt::Formatter) -> core::fmt::Result {
+        write!(f, "row has {} cells, but the table has {} columns", self.actual, self.expected)
+    }
+}
+
+impl core::error::Error for ArityError {}
+
+/// Returned by the `try_` methods that add a row when the row doesn't fit the
+/// table.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum RowError {
+    /// The row doesn't have one cell per column.
+    Arity(ArityError),
+    /// A cell was [set](Row::set) under a header the table doesn't have.
+    UnknownColumn(String),
+}
+
+impl From<ArityError> for RowError {
+    fn from(error: ArityError) -> Self {
+        RowError::Arity(error)
+    }
+}
+
+impl core::fmt::Display for RowError {
+    fn fmt(&self, f: &mut core::fmt::Formatter) -> core::fmt::Result {
+        match self {
+            RowError::Arity(error) => error.fmt(f),
+            RowError::UnknownColumn(name) => write!(f, "no column named `{name}`"),
+        }
+    }
+}
+
+impl core::error::Error for RowError {}
+
+/// Returned when combining tables whose headers don't match.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct HeaderMismatchError {
+    pub expected: Vec<String>,
+    pub actual: Vec<String>,
+}
+
+impl core::fmt::Display for HeaderMismatchError {
+    fn fmt(&self, f: &mut core::fmt::Formatter) -> core::fmt::Result {
+        write!(f, "headers {:?} don't match {:?}", self.actual, self.expected)
+    }
+}
+
+impl core::error::Error for HeaderMismatchError {}
+
+/// Returned when two headers have the same name, which makes looking up a
+/// column by name ambiguous.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DuplicateHeaderError {
+    pub name: String,
+    /// Index of the first header with the name.
+    pub first: usize,
+    /// Index of the later header with the same name.
+    pub duplicate: usize,
+}
+
+impl core::fmt::Display for DuplicateHeaderError {
+    fn fmt(&self, f: &mut core::fmt::Formatter) -> core::fmt::Result {
+        write!(f, "header {:?} at column {} duplicates column {}", self.name, self.duplicate, self.first)
+    }
+}
+
+impl core::error::Error for DuplicateHeaderError {}
+
+/// Returned by [`Table::try_render`] when a table can't be rendered as it is.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TableError {
+    /// A row doesn't have one cell per header.
+    Arity { row: usize, expected: usize, actual: usize },
+    /// A column is too wide for a line to fit in the table's
+    /// [`fit_width`](Table::fit_width) on its own, so its cells are cut or
+    /// run past the width. Only reported by [`Table::validate`].
+    WidthOverflow { column: usize, width: usize, max: usize },
+    /// A cell holds a control character, such as a newline or tab, that
+    /// would break the layout. Escape sequences are allowed.
+    InvalidContent { row: usize, column: usize },
+    /// A header has no text. Only reported by [`Table::validate`].
+    EmptyHeader { column: usize },
+}
+
+impl core::fmt::Display for TableError {
+    fn fmt(&self, f: &mut core::fmt::Formatter) -> core::fmt::Result {
+        match self {
+            TableError::Arity { row, expected, actual } => write!(f, "row {row} has {actual} cells, but the table has {expected} columns"),
+            TableError::WidthOverflow { column, width, max } => write!(f, "column {column} is {width} wide, more than the table width of {max}"),
+            TableError::InvalidContent { row, column } => write!(f, "cell at row {row}, column {column} contains a control character"),
+            TableError::EmptyHeader { column } => write!(f, "header of column {column} is empty"),
+        }
+    }
+}
+
+impl core::error::Error for TableError {}
+
+/// Whether `s` has a control character outside of its escape sequences.
+fn has_control(s: &str) -> bool {
+    if !s.chars().any(|c| c.is_control()) {
+        return false;
+    }
+    if !s.contains('\x1b') {
+        return true;
+    }
+    String::from_utf8_lossy(&ansi::strip(s)).chars().any(|c| c.is_control())
+}
+
+/// Visible width of `s` in terminal columns measured with `strategy`,
+/// ignoring escape sequences.
+///
+/// Text without an ESC byte is measured directly. Stripping allocates, and
+/// skipping it makes measuring a million plain cells about ten times faster.
+fn width(s: &str, strategy: WidthStrategy) -> usize {
+    if !s.as_bytes().contains(&0x1b) {
+        return strategy.str_width(s);
+    }
+    let bytes = ansi::strip(s);
+    strategy.str_width(&String::from_utf8_lossy(&bytes))
+}
+
+/// How the width of text is measured, for terminals and fonts that disagree
+/// with Unicode about emoji and East Asian characters. Each table chooses its
+/// own with [`Table::width_strategy`].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum WidthStrategy {
+    /// Wide characters and most emoji take two columns, and combining marks
+    /// none. Without the `unicode-width` feature this is `CharCount`.
+    #[default]
+    UnicodeWidth,
+    /// Every character takes one column.
+    CharCount,
+    /// Every byte of UTF-8 takes one column, as on terminals that don't
+    /// decode it.
+    ByteLength,
+}
+
+impl WidthStrategy {
+    /// Width of text without escape sequences.
+    fn str_width(self, s: &str) -> usize {
+        match self {
+            #[cfg(feature = "unicode-width")]
+            WidthStrategy::UnicodeWidth => unicode_width::UnicodeWidthStr::width(s),
+            #[cfg(not(feature = "unicode-width"))]
+            WidthStrategy::UnicodeWidth => s.chars().count(),
+            WidthStrategy::CharCount => s.chars().count(),
+            WidthStrategy::ByteLength => s.len(),
+        }
+    }
+
+    fn char_width(self, c: char) -> usize {
+        match self {
+            #[cfg(feature = "unicode-width")]
+            WidthStrategy::UnicodeWidth => unicode_width::UnicodeWidthChar::width(c).unwrap_or(0),
+            #[cfg(not(feature = "unicode-width"))]
+            WidthStrategy::UnicodeWidth => 1,
+            WidthStrategy::CharCount => 1,
+            WidthStrategy::ByteLength => c.len_utf8(),
         }
     }
 }
 
-fn width(s: &str) -> usize {
-    let bytes = strip_ansi_escapes::strip(&s).expect("Failed to strip escape sequences");
-    let s = unsafe { std::str::from_utf8_unchecked(&bytes) };
-    unicode_width::UnicodeWidthStr::width(s)
+static EMPTY: Cell = Cell::Empty;
+
+/// The cell at `index`, or an empty cell if the row is too short.
+fn cell(row: &[Cell], index: usize) -> &Cell {
+    row.get(index).unwrap_or(&EMPTY)
+}
+
+fn cell_width(cell: &Cell, strategy: WidthStrategy) -> usize {
+    match cell {
+        Cell::Str(s) => width(s, strategy),
+        Cell::Shared(s) => width(s, strategy),
+        #[cfg(feature = "compact_str")]
+        Cell::Compact(s) => width(s, strategy),
+        other => other.text().len(),
+    }
+}
+
+/// The text shown for `cell` under `header`, through the header's format.
+/// Empty cells aren't formatted, so that they show the null text.
+fn shown_text<'c>(header: Option<&Header>, cell: &'c Cell) -> Cow<'c, str> {
+    match header.and_then(|h| h.format.as_ref()) {
+        Some(format) if !matches!(cell, Cell::Empty) => Cow::Owned(format.apply(&cell.text())),
+        _ => cell.text(),
+    }
+}
+
+/// Width of the text shown for `cell` under `header`.
+fn shown_width(header: Option<&Header>, cell: &Cell, strategy: WidthStrategy) -> usize {
+    match header.and_then(|h| h.format.as_ref()) {
+        Some(_) if !matches!(cell, Cell::Empty) => width(&shown_text(header, cell), strategy),
+        _ => cell_width(cell, strategy),
+    }
+}
+
+/// Grow `widths` to fit a row whose cells are `cell_widths` wide. A table
+/// without headers takes its column count from its widest row; otherwise
+/// cells past the last header are ignored.
+fn widen(widths: &mut Vec<usize>, headerless: bool, cell_widths: &[usize]) {
+    if headerless && cell_widths.len() > widths.len() {
+        widths.resize(cell_widths.len(), 0);
+    }
+    for (w, cell) in widths.iter_mut()
+        .zip(cell_widths.iter()) {
+        *w = core::cmp::max(*w, *cell);
+    }
+}
+
+/// Add the empty cells of a row to the count of each column.
+fn count_empty(counts: &mut Vec<usize>, cells: &[Cell]) {
+    if cells.len() > counts.len() {
+        counts.resize(cells.len(), 0);
+    }
+    for (count, cell) in counts.iter_mut().zip(cells) {
+        if matches!(cell, Cell::Empty) {
+            *count += 1;
+        }
+    }
 }
 
 impl<T, const N: usize> Table<T, N> {
     pub fn update_widths(&mut self, row: &Row<N>) {
-        for (w, cell) in self.column_widths.iter_mut()
-            .zip(row.cells.iter()) {
-            *w = std::cmp::max(*w, width(&cell));
+        if self.config.fixed_widths.is_some() {
+            return;
         }
+        widen(&mut self.column_widths, self.headers.is_empty(), &RowMeta::measure(&self.headers, &row.cells, self.config.width_strategy).widths);
     }
-}
 
-impl<const N: usize> Table<ModifyHeader, N> {
-    pub fn new() -> Self {
-        Table {
-            headers: Vec::new(),
+    /// Check that no two headers have the same name. Name-keyed APIs such as
+    /// [`set_by_name`](Self::set_by_name) only see the first of them, and JSON
+    /// objects keep only one.
+    pub fn check_headers(&self) -> Result<(), DuplicateHeaderError> {
+        for (duplicate, header) in self.headers.iter().enumerate() {
+            if let Some(first) = self.headers[..duplicate].iter().position(|h| h.text == header.text) {
+                return Err(DuplicateHeaderError { name: header.text.clone(), first, duplicate });
+            }
+        }
+        Ok(())
+    }
+
+    /// Number of data rows, not counting the header.
+    pub fn row_count(&self) -> usize {
+        self.rows.len()
+    }
+
+    /// Number of columns, as defined by the headers.
+    pub fn column_count(&self) -> usize {
+        self.headers.len()
+    }
+
+    /// Whether the table has no data rows.
+    pub fn is_empty(&self) -> bool {
+        self.rows.is_empty()
+    }
+
+    /// The headers, in column order.
+    pub fn headers(&self) -> &[Header] {
+        &self.headers
+    }
+
+    /// Release spare capacity left by removed rows and growing buffers, for a
+    /// table that is kept around after it is built.
+    pub fn shrink_to_fit(&mut self) {
+        self.headers.shrink_to_fit();
+        self.column_widths.shrink_to_fit();
+        self.empty_cells.shrink_to_fit();
+        self.rows.shrink_to_fit();
+        self.meta.shrink_to_fit();
+        for meta in self.meta.iter_mut() {
+            meta.widths.shrink_to_fit();
+        }
+    }
+
+    /// An estimate of the bytes the table holds, on the heap and inline. Row
+    /// tags, the internals of column formats and shared text that isn't
+    /// interned by the table aren't counted.
+    pub fn approx_memory_usage(&self) -> usize {
+        use core::mem::size_of;
+        let headers = self.headers.capacity() * size_of::<Header>()
+            + self.headers.iter().map(|h| h.text.capacity()).sum::<usize>();
+        let meta = self.meta.capacity() * size_of::<RowMeta>()
+            + self.meta.iter().map(|m| m.widths.capacity() * size_of::<usize>()).sum::<usize>();
+        let interned = self.interned.len() * size_of::<Arc<str>>()
+            + self.interned.iter().map(|s| s.len()).sum::<usize>();
+        let printed = self.printed.as_ref().map_or(0, |p| p.widths.capacity() * size_of::<usize>());
+        size_of::<Self>()
+            + headers
+            + (self.column_widths.capacity() + self.empty_cells.capacity()) * size_of::<usize>()
+            + self.rows.heap_size()
+            + meta
+            + interned
+            + printed
+    }
+
+    /// Iterate over the data rows.
+    pub fn rows(&self) -> impl Iterator<Item = &[Cell]> + '_ {
+        self.rows.iter()
+    }
+
+    /// Indices of the rows for which `predicate` returns true.
+    pub fn find_rows<F: FnMut(&[Cell]) -> bool>(&self, mut predicate: F) -> Vec<usize> {
+        self.rows.iter()
+            .enumerate()
+            .filter(|(_, row)| predicate(row))
+            .map(|(i, _)| i)
+            .collect()
+    }
+
+    /// The tag attached to row `index` with [`Row::tag`], if it has one of type `V`.
+    pub fn row_tag<V: Any>(&self, index: usize) -> Option<&V> {
+        self.meta.get(index)?.tag.as_ref()?.0.downcast_ref()
+    }
+
+    /// Indices of the rows with a tag of type `V` for which `predicate` returns true.
+    pub fn find_rows_by_tag<V: Any, F: FnMut(&V) -> bool>(&self, mut predicate: F) -> Vec<usize> {
+        (0..self.meta.len())
+            .filter(|&i| self.row_tag(i).is_some_and(&mut predicate))
+            .collect()
+    }
+
+    /// Iterate over the cells of a column, identified by index or header text.
+    /// Yields one item per row, with an empty cell for rows that are missing it.
+    /// Returns `None` if there is no such column.
+    pub fn column<C: ColumnIndex>(&self, column: C) -> Option<impl Iterator<Item = &Cell> + '_> {
+        let index = column.index_in(&self.headers)?;
+        Some(self.rows.iter().map(move |row| cell(row, index)))
+    }
+
+    fn from_parts(headers: Vec<Header>, rows: Vec<Vec<Cell>>, config: Config) -> Self {
+        let meta = rows.iter().map(|row| RowMeta::measure(&headers, row, config.width_strategy)).collect();
+        let mut table = Table {
+            headers,
             column_widths: Vec::new(),
-            rows: Vec::new(),
-            skip_header: false,
+            empty_cells: Vec::new(),
+            meta,
+            rows: rows.into(),
+            interned: Default::default(),
+            config,
+            printed: None,
             _pd: PhantomData,
-        }
+        };
+        table.recompute_widths();
+        table
     }
 
-    pub fn header<H: Into<Header>>(mut self, header: H) -> Table<ModifyHeader, N> {
-        let header = header.into();
-        let width = width(&header.text);
-        self.headers.push(header);
-        self.column_widths.push(width);
+    /// The same table in another typestate.
+    fn into_state<U>(self) -> Table<U, N> {
         Table {
             headers: self.headers,
             column_widths: self.column_widths,
+            empty_cells: self.empty_cells,
             rows: self.rows,
-            skip_header: self.skip_header,
+            meta: self.meta,
+            interned: self.interned,
+            config: self.config,
+            printed: self.printed,
             _pd: PhantomData,
         }
     }
 
-    pub fn row(mut self, row: Row<N>) -> Table<ModifyRows, N> {
-        self.update_widths(&row);
+    /// A table with these headers, widths and settings, holding `rows`.
+    fn with_rows(&self, rows: rows::Rows) -> Table<ModifyRows, N> {
         Table {
-            headers: self.headers,
-            column_widths: self.column_widths,
-            rows: vec![row.cells],
-            skip_header: self.skip_header,
+            headers: self.headers.clone(),
+            column_widths: self.column_widths.clone(),
+            empty_cells: self.empty_cells.clone(),
+            meta: rows.iter().map(|row| RowMeta::measure(&self.headers, row, self.config.width_strategy)).collect(),
+            rows,
+            interned: Default::default(),
+            config: self.config.clone(),
+            printed: None,
             _pd: PhantomData,
         }
     }
 
-    pub fn end_header(self) -> Table<ModifyRows, N> {
-        Table {
-            headers: self.headers,
-            column_widths: self.column_widths,
-            rows: Vec::new(),
-            skip_header: self.skip_header,
-            _pd: PhantomData,
+    fn recompute_widths(&mut self) {
+        self.column_widths = self.headers.iter()
+            .map(|h| width(&h.text, self.config.width_strategy))
+            .collect();
+        let headerless = self.headers.is_empty();
+        for meta in self.meta.iter() {
+            widen(&mut self.column_widths, headerless, &meta.widths);
         }
+        self.recount_empty();
     }
-}
 
-impl<const N: usize> Table<ModifyRows, N> {
-    pub fn row(mut self, row: Row<N>) -> Self {
-        self.update_widths(&row);
-        self.rows.push(row.cells);
-        self
+    fn recount_empty(&mut self) {
+        self.empty_cells.clear();
+        for row in self.rows.iter() {
+            count_empty(&mut self.empty_cells, row);
+        }
     }
-}
 
-fn format(s: &str, target_width: usize, alignment: Alignment) -> String {
-    let width = width(s);
-    let target_width = std::cmp::max(target_width, 8);
-    let padding = target_width - width;
-    match alignment {
-        Alignment::Left => s.to_string() + &" ".repeat(padding),
-        Alignment::Right => " ".repeat(padding) + s,
-        Alignment::Center => {
-            let left = padding / 2;
-            let right = padding - left;
-            " ".repeat(left) + s + &" ".repeat(right)
+    fn recompute_column_width(&mut self, col: usize) {
+        if col >= self.column_widths.len() {
+            return;
         }
+        let header_width = self.headers.get(col).map_or(0, |header| width(&header.text, self.config.width_strategy));
+        let w = self.meta.iter()
+            .filter_map(|meta| meta.widths.get(col).copied())
+            .fold(header_width, core::cmp::max);
+        self.column_widths[col] = w;
+    }
+
+    /// Get the cell at `row`, `col`, if it exists.
+    pub fn get(&self, row: usize, col: usize) -> Option<&Cell> {
+        self.rows.get(row)?.get(col)
     }
-}
 
-impl std::fmt::Display for Table<ModifyRows> {
-    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
-        if !self.skip_header {
-            for (header, width) in self.headers.iter()
-                .zip(self.column_widths.iter()) {
-                let header = format(&header.text, *width, header.alignment);
-                write!(f, "{} ", header)?;
+    /// Replace the cell at `row`, `col` and return the previous value. Returns
+    /// `None` and leaves the table untouched if the cell doesn't exist.
+    pub fn set<V: Into<Cell>>(&mut self, row: usize, col: usize, value: V) -> Option<Cell> {
+        self.rows.get(row)?.get(col)?;
+        let value = self.admit_cell(col, value.into());
+        let cell = &mut self.rows.get_mut(row).expect("checked above")[col];
+        let new_width = shown_width(self.headers.get(col), &value, self.config.width_strategy);
+        let old_width = core::mem::replace(&mut self.meta[row].widths[col], new_width);
+        let old = core::mem::replace(cell, value);
+        let was_empty = matches!(old, Cell::Empty);
+        let is_empty = matches!(self.rows[row][col], Cell::Empty);
+        if was_empty != is_empty {
+            if self.empty_cells.len() <= col {
+                self.empty_cells.resize(col + 1, 0);
+            }
+            match is_empty {
+                true => self.empty_cells[col] += 1,
+                false => self.empty_cells[col] = self.empty_cells[col].saturating_sub(1),
             }
-            writeln!(f)?;
         }
-        for row in self.rows.iter() {
-            for (cell, width) in row.iter()
-                .zip(self.column_widths.iter()) {
-                let cell = format(cell, *width, Alignment::Left);
-                write!(f, "{cell} ")?;
+        // Only rescan the column when its widest cell may have shrunk.
+        if let (None, Some(column_width)) = (&self.config.fixed_widths, self.column_widths.get_mut(col)) {
+            if new_width >= *column_width {
+                *column_width = new_width;
+            } else if old_width == *column_width {
+                self.recompute_column_width(col);
             }
-            writeln!(f)?;
         }
-        Ok(())
+        Some(old)
     }
-}
 
-pub struct Row<const N: usize> {
-    cells: Vec<String>,
-}
+    /// Like [`set`](Self::set), with the column identified by header text.
+    pub fn set_by_name<V: Into<Cell>>(&mut self, row: usize, header: &str, value: V) -> Option<Cell> {
+        let col = header.index_in(&self.headers)?;
+        self.set(row, col, value)
+    }
 
-impl<const N: usize> Row<N> {
-    pub fn new() -> Self {
-        Row {
-            cells: Vec::new(),
-        }
+    /// Don't print the header line when rendering.
+    pub fn without_header(mut self) -> Self {
+        self.config.skip_header = true;
+        self
     }
 
-    pub fn cell(mut self, cell: &str) -> Row<N> {
-        self.cells.push(cell.to_string());
-        Row {
-            cells: self.cells,
-        }
+    /// Choose whether the header line is printed when rendering.
+    pub fn set_skip_header(&mut self, skip: bool) {
+        self.config.skip_header = skip;
     }
-}
 
-#[cfg(test)]
-mod tests {
-    use super::*;
+    /// Set how rows that don't match the number of headers are handled.
+    pub fn row_length_policy(mut self, policy: RowLengthPolicy) -> Self {
+        self.config.row_length_policy = policy;
+        self
+    }
 
-    #[test]
-    fn it_works() {
-        let table = Table::new()
-            .header("Name")
-            .header("Age")
-            .row(Row::new().cell("Alice").cell("20"))
-            .row(Row::new().cell("Bob").cell("30"));
-        println!("{}", table);
-        assert_eq!(table.to_string(),
-                   "Name     Age      \n".to_owned() +
-                       "Alice    20       \n" +
-                       "Bob      30       \n");
+    /// Show `text` in place of empty cells, such as NULLs from a database.
+    pub fn null_text(mut self, text: &str) -> Self {
+        self.config.null_text = text.to_string();
+        self
+    }
+
+    /// Print `message` under the header when the table has no rows.
+    pub fn empty_message(mut self, message: &str) -> Self {
+        self.config.empty_message = Some(message.to_string());
+        self
+    }
+
+    /// Choose the delimiter, quoting and line endings of
+    /// [`OutputFormat::Csv`].
+    pub fn csv_options(mut self, options: CsvOptions) -> Self {
+        self.config.csv = options;
+        self
     }
-}
\ No newline at end of file
+
+    /// Choose what is rendered when the table has no rows.
+    pub fn empty_table(mut self, policy: EmptyTablePolicy) -> Self {
+        self.config.empty_table = policy;
+        self
+    }
+
+    /// Render at most `max` rows, followed by a line counting the rest, so
+    /// that an unexpectedly large table can't flood the terminal.
+    pub fn max_render_rows(mut self, max: usize) -> Self {
+        self.config.max_render_rows = Some(max);
+        self
+    }
+
+    /// Choose what happens to replacement characters left by invalid UTF-8 in
+    /// the cells added from now on.
+    pub fn invalid_text(mut self, policy: InvalidTextPolicy) -> Self {
+        self.config.invalid_text = policy;
+        self
+    }
+
+    /// Choose what happens to line breaks in the cells added from now on.
+    pub fn newlines(mut self, policy: NewlinePolicy) -> Self {
+        self.config.newlines = policy;
+        self
+    }
+
+    /// Align the data cells of every column that doesn't choose its own
+    /// alignment, directly or through its [`ColumnType`]. Defaults to left.
+    pub fn default_alignment(mut self, alignment: Alignment) -> Self {
+        self.config.default_alignment = alignment;
+        self
+    }
+
+    /// Choose how escape sequences in cells are treated.
+    pub fn ansi_policy(mut self, policy: AnsiPolicy) -> Self {
+        self.config.ansi = policy;
+        self
+    }
+
+    /// Render each column at the given width, truncating longer cells, and
+    /// stop tracking how wide the cells are, so that adding a row costs no
+    /// more than storing it. Columns past the end of `widths` get the default
+    /// minimum width.
+    pub fn fixed_widths(mut self, widths: &[usize]) -> Self {
+        self.config.fixed_widths = Some(widths.to_vec());
+        self
+    }
+
+    /// Keep rendered lines within `width` columns, such as the width of the
+    /// terminal, using `policy` when the columns are wider. This applies to the
+    /// plain format, as rendered by `Display`.
+    pub fn fit_width(mut self, width: usize, policy: OverflowPolicy) -> Self {
+        self.config.fit_width = Some((width, policy));
+        self
+    }
+
+    /// Measure text with `strategy`, for terminals and fonts that disagree
+    /// with Unicode about how wide some characters are. Cells already in the
+    /// table are measured again.
+    pub fn width_strategy(mut self, strategy: WidthStrategy) -> Self {
+        self.config.width_strategy = strategy;
+        for (meta, row) in self.meta.iter_mut().zip(self.rows.iter()) {
+            meta.widths = RowMeta::measure(&self.headers, row, strategy).widths;
+        }
+        self.recompute_widths();
+        self
+    }
+
+    /// Bring `cells` to the length of the headers according to the row length policy.
+    fn fit_row(&mut self, mut cells: Vec<Cell>) -> Result<Vec<Cell>, ArityError> {
+        let expected = match (self.headers.len(), self.config.headerless_columns) {
+            (0, None) => return Ok(cells),
+            (0, Some(0)) => {
+                self.config.headerless_columns = Some(cells.len());
+                return Ok(cells);
+            }
+            (0, Some(columns)) => columns,
+            (headers, _) => headers,
+        };
+        if cells.len() == expected {
+            return Ok(cells);
+        }
+        let error = ArityError {
+            expected,
+            actual: cells.len(),
+        };
+        match self.config.row_length_policy {
+            RowLengthPolicy::Error => return Err(error),
+            RowLengthPolicy::PadWithEmpty if cells.len() > expected => return Err(error),
+            RowLengthPolicy::PadWithEmpty | RowLengthPolicy::Truncate => {
+                cells.resize(expected, Cell::Empty);
+            }
+            RowLengthPolicy::GrowHeaders if cells.len() < expected => {
+                cells.resize(expected, Cell::Empty);
+            }
+            RowLengthPolicy::GrowHeaders => {
+                if self.headers.is_empty() {
+                    self.config.headerless_columns = Some(cells.len());
+                } else {
+                    self.headers.resize_with(cells.len(), Header::default);
+                }
+                self.column_widths.resize(cells.len(), 0);
+                let len = cells.len();
+                self.rows.edit_each(|row| row.resize(len, Cell::Empty));
+                for meta in self.meta.iter_mut() {
+                    meta.widths.resize(len, 0);
+                }
+                self.recount_empty();
+            }
+        }
+        Ok(cells)
+    }
+
+    /// Apply the invalid text, newline and ANSI policies to `value`, and
+    /// intern it if column `col` is interned. Column formats are applied when
+    /// the cell is shown, so the stored value keeps its type.
+    fn admit_cell(&mut self, col: usize, value: Cell) -> Cell {
+        let mut value = self.config.newlines.apply(self.config.invalid_text.apply(value));
+        if self.config.ansi == AnsiPolicy::StripBeforeStore {
+            if let Some(Cow::Owned(text)) = value.as_str().map(ansi::strip_str) {
+                value = Cell::Str(text);
+            }
+        }
+        let Some(header) = self.headers.get(col) else { return value };
+        let Some(text) = value.as_str().filter(|_| header.intern) else { return value };
+        let shared = match self.interned.get(text) {
+            Some(shared) => shared.clone(),
+            None => {
+                let shared: Arc<str> = text.into();
+                self.interned.insert(shared.clone());
+                shared
+            }
+        };
+        Cell::Shared(shared)
+    }
+
+    /// Apply the row length policy and column formats to `row` and account for its widths.
+    fn admit(&mut self, row: Row<N>) -> Result<(Vec<Cell>, RowMeta), ArityError> {
+        let (cells, tag) = self.prepare(row)?;
+        let meta = RowMeta {
+            tag,
+            ..RowMeta::measure(&self.headers, &cells, self.config.width_strategy)
+        };
+        if self.config.fixed_widths.is_none() {
+            widen(&mut self.column_widths, self.headers.is_empty(), &meta.widths);
+        }
+        count_empty(&mut self.empty_cells, &cells);
+        Ok((cells, meta))
+    }
+
+    /// The part of `admit` that doesn't measure the cells.
+    fn prepare(&mut self, mut row: Row<N>) -> Result<(Vec<Cell>, Option<RowTag>), ArityError> {
+        if !row.named.is_empty() && row.cells.len() < self.headers.len() {
+            row.cells.resize(self.headers.len(), Cell::Empty);
+        }
+        for (header, cell) in row.named {
+            let index = header.as_str().index_in(&self.headers)
+                .unwrap_or_else(|| panic!("no column named `{header}`"));
+            row.cells[index] = cell;
+        }
+        for (i, alignment) in row.alignments {
+            if let Some(header) = self.headers.get_mut(i) {
+                header.cell_alignment.get_or_insert(alignment);
+            }
+        }
+        let cells = self.fit_row(row.cells)?;
+        let cells: Vec<Cell> = cells.into_iter()
+            .enumerate()
+            .map(|(i, cell)| self.admit_cell(i, cell))
+            .collect();
+        Ok((cells, row.tag))
+    }
+
+    /// Admit `row` and insert it, with its tag, at `index`.
+    fn insert_admitted(&mut self, index: usize, row: Row<N>) -> Result<(), ArityError> {
+        let (cells, meta) = self.admit(row)?;
+        self.rows.insert(index, cells);
+        self.meta.insert(index, meta);
+        Ok(())
+    }
+
+    /// Keep the rows for which `keep` returns true.
+    fn retain_rows<F: FnMut(&[Cell], &RowMeta) -> bool>(&mut self, mut keep: F) {
+        let order: Vec<usize> = self.rows.iter()
+            .zip(self.meta.iter())
+            .enumerate()
+            .filter(|(_, (row, meta))| keep(row, meta))
+            .map(|(i, _)| i)
+            .collect();
+        self.reorder_rows(&order);
+    }
+
+    /// Rearrange the rows so that row `i` is the old row `order[i]`. Rows not
+    /// in `order` are dropped.
+    fn reorder_rows(&mut self, order: &[usize]) {
+        self.rows.reorder(order);
+        let mut meta: Vec<Option<RowMeta>> = core::mem::take(&mut self.meta).into_iter().map(Some).collect();
+        self.meta = order.iter()
+            .map(|&i| meta[i].take().expect("each row is moved once"))
+            .collect();
+    }
+
+    /// Check that `row` has one cell per column and that its named cells
+    /// have a header. A table without headers or rows accepts any number of
+    /// cells, and its first row sets the column count.
+    fn check_arity(&self, row: &Row<N>) -> Result<(), RowError> {
+        if let Some((name, _)) = row.named.iter().find(|(name, _)| name.as_str().index_in(&self.headers).is_none()) {
+            return Err(RowError::UnknownColumn(name.clone()));
+        }
+        let expected = match (self.headers.len(), self.config.headerless_columns) {
+            // Without a declared count, the widest row sets it.
+            (0, None) => self.column_widths.len(),
+            (0, Some(columns)) => columns,
+            (headers, _) => headers,
+        };
+        let actual = if row.named.is_empty() {
+            row.cells.len()
+        } else {
+            core::cmp::max(row.cells.len(), self.headers.len())
+        };
+        let first_headerless = self.headers.is_empty()
+            && self.rows.is_empty()
+            && matches!(self.config.headerless_columns, None | Some(0));
+        if actual != expected && !first_headerless {
+            return Err(ArityError {
+                expected,
+                actual,
+            }.into());
+        }
+        Ok(())
+    }
+
+    /// Append a row, or return an error if it doesn't have one cell per column
+    /// or sets a cell under a header the table doesn't have.
+    pub fn try_push_row(&mut self, row: Row<N>) -> Result<(), RowError> {
+        self.check_arity(&row)?;
+        Ok(self.insert_admitted(self.rows.len(), row)?)
+    }
+
+    /// Insert a row at `index`, shifting later rows down.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `index > row_count`, if the row length policy rejects the
+    /// row, or if the row sets a cell under a header the table doesn't have.
+    pub fn insert_row(&mut self, index: usize, row: Row<N>) {
+        assert!(index <= self.rows.len(), "insertion index (is {index}) should be <= row count (is {})", self.rows.len());
+        self.insert_admitted(index, row).unwrap_or_else(|e| panic!("{e}"));
+    }
+
+    /// Remove and return the row at `index`, shifting later rows up.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `index` is out of bounds.
+    pub fn remove_row(&mut self, index: usize) -> Vec<Cell> {
+        let row = self.rows.remove(index);
+        self.meta.remove(index);
+        self.recompute_widths();
+        row
+    }
+
+    /// Attach a tag to row `index`, replacing any it had. See [`Row::tag`].
+    ///
+    /// # Panics
+    ///
+    /// Panics if `index` is out of bounds.
+    pub fn set_row_tag<V: Any + Send + Sync>(&mut self, index: usize, value: V) {
+        assert!(index < self.rows.len(), "row index (is {index}) should be < row count (is {})", self.rows.len());
+        self.meta[index].tag = Some(RowTag(Arc::new(value)));
+    }
+
+    /// Build a table column by column. The first column added to an empty
+    /// table sets the number of rows, and every later column must match it.
+    pub fn add_column_data<H: Into<Header>, V: Into<Cell>>(&mut self, header: H, values: Vec<V>) -> Result<(), ColumnLengthError> {
+        if self.headers.is_empty() && self.rows.is_empty() {
+            self.rows = vec![Vec::new(); values.len()].into();
+            self.meta = vec![RowMeta::default(); values.len()];
+        }
+        self.add_column(header, values)
+    }
+
+    /// Append a column, with `values` supplying one cell per existing row.
+    /// Leaves the table untouched and returns an error if the lengths differ.
+    /// The columns of a table without headers get empty headers.
+    pub fn add_column<H: Into<Header>, V: Into<Cell>>(&mut self, header: H, values: Vec<V>) -> Result<(), ColumnLengthError> {
+        if values.len() != self.rows.len() {
+            return Err(ColumnLengthError {
+                expected: self.rows.len(),
+                actual: values.len(),
+            });
+        }
+        let columns = self.rows.iter().map(<[Cell]>::len).fold(self.headers.len(), core::cmp::max);
+        self.headers.resize_with(columns, Header::default);
+        self.headers.push(header.into());
+        let values: Vec<Cell> = values.into_iter()
+            .map(|v| self.admit_cell(columns, v.into()))
+            .collect();
+        let strategy = self.config.width_strategy;
+        let header = &self.headers[columns];
+        let value_widths: Vec<usize> = values.iter()
+            .map(|value| shown_width(Some(header), value, strategy))
+            .collect();
+        let w = value_widths.iter().copied().fold(width(&header.text, strategy), core::cmp::max);
+        let mut values = values.into_iter();
+        self.rows.edit_each(|row| {
+            row.resize(columns, Cell::Empty);
+            row.push(values.next().expect("one value per row"));
+        });
+        for (meta, value_width) in self.meta.iter_mut().zip(value_widths) {
+            meta.widths.resize(columns, 0);
+            meta.widths.push(value_width);
+        }
+        self.column_widths.resize(columns, 0);
+        self.column_widths.push(w);
+        self.recount_empty();
+        Ok(())
+    }
+
+    /// Append a column, filling every existing row with `default`.
+    pub fn add_column_with_default<H: Into<Header>, V: Into<Cell>>(&mut self, header: H, default: V) {
+        let values = vec![default.into(); self.rows.len()];
+        self.add_column(header, values).expect("one value per row");
+    }
+
+    /// Remove a column, identified by index or header text, and return its
+    /// header. Returns `None` if there is no such column.
+    pub fn remove_column<C: ColumnIndex>(&mut self, column: C) -> Option<Header> {
+        let index = column.index_in(&self.headers)?;
+        self.rows.edit_each(|row| {
+            if index < row.len() {
+                row.remove(index);
+            }
+        });
+        for meta in self.meta.iter_mut() {
+            if index < meta.widths.len() {
+                meta.widths.remove(index);
+            }
+        }
+        self.column_widths.remove(index);
+        if index < self.empty_cells.len() {
+            self.empty_cells.remove(index);
+        }
+        Some(self.headers.remove(index))
+    }
+
+    /// Change the text of a header, identified by index or current text, and
+    /// return the old text. Returns `None` if there is no such column.
+    pub fn rename_header<C: ColumnIndex>(&mut self, column: C, new: &str) -> Option<String> {
+        let index = column.index_in(&self.headers)?;
+        let old = core::mem::replace(&mut self.headers[index].text, new.to_string());
+        self.recompute_column_width(index);
+        Some(old)
+    }
+
+    /// Convert every header's text with `case`, typically after building the
+    /// table from records whose headers are field names.
+    pub fn header_case(mut self, case: HeaderCase) -> Self {
+        for header in self.headers.iter_mut() {
+            header.text = case.apply(&header.text);
+        }
+        self.recompute_widths();
+        self
+    }
+
+    /// Move the rows of `other` onto the end of this table. Both tables must
+    /// have the same header names, in the same order.
+    pub fn append<U>(&mut self, other: Table<U, N>) -> Result<(), HeaderMismatchError> {
+        let same = self.headers.len() == other.headers.len()
+            && self.headers.iter().zip(other.headers.iter()).all(|(a, b)| a.text == b.text);
+        if !same {
+            return Err(HeaderMismatchError {
+                expected: self.headers.iter().map(|h| h.text.clone()).collect(),
+                actual: other.headers.into_iter().map(|h| h.text).collect(),
+            });
+        }
+        if self.column_widths.len() < other.column_widths.len() {
+            self.column_widths.resize(other.column_widths.len(), 0);
+        }
+        for (w, o) in self.column_widths.iter_mut().zip(other.column_widths) {
+            *w = core::cmp::max(*w, o);
+        }
+        if self.empty_cells.len() < other.empty_cells.len() {
+            self.empty_cells.resize(other.empty_cells.len(), 0);
+        }
+        for (count, o) in self.empty_cells.iter_mut().zip(other.empty_cells) {
+            *count += o;
+        }
+        self.rows.extend(other.rows);
+        self.meta.extend(other.meta);
+        Ok(())
+    }
+
+    /// Remove rows that are exact duplicates of an earlier row.
+    pub fn dedup_rows(&mut self) {
+        let mut seen = alloc::collections::BTreeSet::new();
+        self.retain_rows(|row, _| seen.insert(row.to_vec()));
+        self.recompute_widths();
+    }
+
+    /// Remove rows whose cell in `column` duplicates that of an earlier row.
+    /// Does nothing if there is no such column.
+    pub fn dedup_by_column<C: ColumnIndex>(&mut self, column: C) {
+        let Some(index) = column.index_in(&self.headers) else { return };
+        let mut seen = alloc::collections::BTreeSet::new();
+        self.retain_rows(|row, _| seen.insert(row.get(index).cloned()));
+        self.recompute_widths();
+    }
+
+    /// Split the rows into tables of at most `size` rows each. Every chunk has
+    /// the same headers and column widths as this table, so they line up when
+    /// printed one after another.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `size` is 0.
+    pub fn chunks(&self, size: usize) -> impl Iterator<Item = Table<ModifyRows, N>> + '_ {
+        assert!(size != 0, "chunk size must be non-zero");
+        (0..self.rows.len()).step_by(size).map(move |start| {
+            let end = core::cmp::min(start + size, self.rows.len());
+            Table {
+                meta: self.meta[start..end].to_vec(),
+                ..self.with_rows(self.rows.slice(start, end))
+            }
+        })
+    }
+
+    /// Keep only the first `n` rows, as for a `--limit` flag. Column widths
+    /// are recomputed to fit the rows that are left.
+    pub fn take(mut self, n: usize) -> Self {
+        self.rows.truncate(n);
+        self.meta.truncate(n);
+        self.recompute_widths();
+        self
+    }
+
+    /// Drop the first `n` rows, as for an `--offset` flag. Column widths are
+    /// recomputed to fit the rows that are left.
+    pub fn skip(mut self, n: usize) -> Self {
+        self.rows.drain_front(n);
+        self.meta.drain(..core::cmp::min(n, self.meta.len()));
+        self.recompute_widths();
+        self
+    }
+
+    /// Keep only the rows with a tag of type `V` for which `predicate` returns
+    /// true. Column widths are recomputed to fit the rows that are left.
+    pub fn filter_by_tag<V: Any, F: FnMut(&V) -> bool>(mut self, mut predicate: F) -> Self {
+        self.retain_rows(|_, meta| meta.tag.as_ref().and_then(|tag| tag.0.downcast_ref()).is_some_and(&mut predicate));
+        self.recompute_widths();
+        self
+    }
+
+    /// Render the table as pages of at most `page_size` rows, each with the
+    /// header and a `page 2/5` footer. Columns are as wide as for the whole
+    /// table, so pages line up. An empty table renders one page.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `page_size` is 0.
+    pub fn paginate(&self, page_size: usize) -> impl Iterator<Item = String> + '_ {
+        let pages = self.rows.len().div_ceil(page_size).max(1);
+        (0..pages).map(move |i| {
+            let start = i * page_size;
+            let end = core::cmp::min(start + page_size, self.rows.len());
+            let page = self.with_rows(self.rows.slice(start, end));
+            format!("{page}page {}/{pages}\n", i + 1)
+        })
+    }
+
+    /// Render the rows added since the last call, for a table that keeps
+    /// growing after it is first printed. The first call renders the header
+    /// and fixes the column widths; later rows are truncated to fit them.
+    pub fn render_new_rows(&mut self) -> String {
+        let mut out = String::new();
+        let printed = match self.printed.take() {
+            Some(printed) => printed,
+            None => {
+                let widths = self.display_widths();
+                if !self.config.skip_header && !self.headers.is_empty() {
+                    self.write_plain_header(&mut out, &widths).expect("writing to a String can't fail");
+                }
+                Printed { rows: 0, widths }
+            }
+        };
+        for (row, meta) in self.rows.iter().zip(self.meta.iter()).skip(printed.rows) {
+            self.write_plain_row(&mut out, row, &meta.widths, &printed.widths).expect("writing to a String can't fail");
+        }
+        self.printed = Some(Printed { rows: self.rows.len(), ..printed });
+        out
+    }
+
+    /// Sort the rows by `column`, comparing cells according to the column's
+    /// type, or by their own values if it has none. The sort is stable. Does nothing if
+    /// there is no such column.
+    pub fn sort_by_column<C: ColumnIndex>(&mut self, column: C) {
+        self.sort_by_columns(&[(column, SortOrder::Ascending)]);
+    }
+
+    /// Like [`sort_by_column`](Self::sort_by_column), largest first. Equal
+    /// rows keep their order.
+    pub fn sort_by_column_descending<C: ColumnIndex>(&mut self, column: C) {
+        self.sort_by_columns(&[(column, SortOrder::Descending)]);
+    }
+
+    /// Reverse the order of the rows.
+    pub fn reverse_rows(&mut self) {
+        let order: Vec<usize> = (0..self.rows.len()).rev().collect();
+        self.reorder_rows(&order);
+    }
+
+    /// Sort the rows by several columns, comparing by the first and breaking
+    /// ties with the next. The sort is stable, and columns that don't exist are
+    /// ignored.
+    pub fn sort_by_columns<C: ColumnIndex>(&mut self, columns: &[(C, SortOrder)]) {
+        let keys: Vec<(usize, Option<ColumnType>, SortOrder)> = columns.iter()
+            .filter_map(|(column, order)| {
+                let index = column.index_in(&self.headers)?;
+                Some((index, self.headers[index].column_type, *order))
+            })
+            .collect();
+        if keys.is_empty() {
+            return;
+        }
+        let compare = |a: &[Cell], b: &[Cell]| {
+            keys.iter()
+                .map(|&(index, kind, order)| {
+                    let (a, b) = (cell(a, index), cell(b, index));
+                    let ordering = match kind {
+                        Some(kind) => kind.compare(a, b),
+                        None => a.cmp(b),
+                    };
+                    match order {
+                        SortOrder::Ascending => ordering,
+                        SortOrder::Descending => ordering.reverse(),
+                    }
+                })
+                .find(|ordering| ordering.is_ne())
+                .unwrap_or(core::cmp::Ordering::Equal)
+        };
+        let mut order: Vec<usize> = (0..self.rows.len()).collect();
+        order.sort_by(|&a, &b| compare(&self.rows[a], &self.rows[b]));
+        self.reorder_rows(&order);
+    }
+
+    /// Swap rows and columns. The first column becomes the header, and the
+    /// headers become the first column.
+    pub fn transpose(self) -> Table<ModifyRows, N> {
+        let ncols = self.headers.len();
+        let mut headers = Vec::with_capacity(self.rows.len() + 1);
+        let mut rows: Vec<Vec<Cell>> = Vec::with_capacity(ncols.saturating_sub(1));
+        // Formats follow columns, which become rows, so the cells are
+        // formatted here.
+        for row in self.rows.iter() {
+            headers.push(self.shown_text(0, cell(row, 0)).into_owned().into());
+        }
+        for (i, header) in self.headers.iter().enumerate().skip(1) {
+            let mut new_row = Vec::with_capacity(self.rows.len() + 1);
+            new_row.push(header.text.clone().into());
+            new_row.extend(self.rows.iter().map(|row| self.shown_cell(i, cell(row, i))));
+            rows.push(new_row);
+        }
+        if let Some(first) = self.headers.into_iter().next() {
+            headers.insert(0, Header { format: None, ..first });
+        }
+        for row in rows.iter_mut() {
+            row.resize(headers.len(), Cell::Empty);
+        }
+        Table::from_parts(headers, rows, self.config)
+    }
+}
+
+impl<T, const N: usize> Default for Table<T, N> {
+    fn default() -> Self {
+        Table {
+            headers: Vec::new(),
+            column_widths: Vec::new(),
+            empty_cells: Vec::new(),
+            rows: Default::default(),
+            meta: Vec::new(),
+            interned: Default::default(),
+            config: Config::default(),
+            printed: None,
+            _pd: PhantomData,
+        }
+    }
+}
+
+impl Table {
+    /// Start a table. Tables with a nonzero `N` start from `Table::default()`.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Start a table with room for `rows` rows of `cols` columns, to avoid
+    /// reallocating while loading a large table.
+    pub fn with_capacity(rows: usize, cols: usize) -> Self {
+        Table {
+            headers: Vec::with_capacity(cols),
+            column_widths: Vec::with_capacity(cols),
+            rows: rows::Rows::with_capacity(rows, rows * cols),
+            meta: Vec::with_capacity(rows),
+            ..Self::default()
+        }
+    }
+}
+
+impl<const N: usize> Table<ModifyHeader, N> {
+    pub fn header<H: Into<Header>>(mut self, header: H) -> Table<ModifyHeader, N> {
+        let header = header.into();
+        let width = width(&header.text, self.config.width_strategy);
+        self.headers.push(header);
+        self.column_widths.push(width);
+        self
+    }
+
+    /// Like [`header`](Self::header), but returns an error if a header with
+    /// the same name was already added.
+    pub fn try_header<H: Into<Header>>(self, header: H) -> Result<Table<ModifyHeader, N>, DuplicateHeaderError> {
+        let header = header.into();
+        if let Some(first) = self.headers.iter().position(|h| h.text == header.text) {
+            return Err(DuplicateHeaderError { name: header.text, first, duplicate: self.headers.len() });
+        }
+        Ok(self.header(header))
+    }
+
+    /// End the header and add the first row.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the row length policy rejects the row, or if the row sets a
+    /// cell under a header the table doesn't have.
+    pub fn row(mut self, row: Row<N>) -> Table<ModifyRows, N> {
+        self.rows.clear();
+        self.meta.clear();
+        self.empty_cells.clear();
+        self.insert_admitted(0, row).unwrap_or_else(|e| panic!("{e}"));
+        self.into_state()
+    }
+
+    /// Like [`row`](Self::row), but returns an error if the row doesn't have
+    /// one cell per header or sets a cell under a header the table doesn't have.
+    pub fn try_row(self, row: Row<N>) -> Result<Table<ModifyRows, N>, RowError> {
+        self.check_arity(&row)?;
+        Ok(self.row(row))
+    }
+
+    pub fn end_header(mut self) -> Table<ModifyRows, N> {
+        self.rows.clear();
+        self.meta.clear();
+        self.empty_cells.clear();
+        self.into_state()
+    }
+}
+
+impl Table<ModifyRows> {
+    /// Start a table without headers, for aligning plain data. Rows are fitted
+    /// to `columns` cells by the row length policy; with `columns` of 0 the
+    /// first row sets the count.
+    pub fn headerless(columns: usize) -> Self {
+        let mut table = Table::default().end_header();
+        table.column_widths = vec![0; columns];
+        table.config.headerless_columns = Some(columns);
+        table
+    }
+}
+
+impl<const N: usize> Table<ModifyRows, N> {
+    /// Build a table from already-collected data. Every row must have one cell
+    /// per header.
+    pub fn from_rows<H, C>(headers: Vec<H>, rows: Vec<Vec<C>>) -> Result<Self, ArityError>
+    where
+        H: Into<Header>,
+        C: Into<Cell>,
+    {
+        let headers: Vec<Header> = headers.into_iter().map(Into::into).collect();
+        let column_widths: Vec<usize> = headers.iter().map(|h| width(&h.text, WidthStrategy::default())).collect();
+        let mut table = Table {
+            headers,
+            column_widths,
+            empty_cells: Vec::new(),
+            rows: rows::Rows::with_capacity(rows.len(), 0),
+            meta: Vec::with_capacity(rows.len()),
+            interned: Default::default(),
+            config: Config::default(),
+            printed: None,
+            _pd: PhantomData,
+        };
+        for row in rows {
+            if row.len() != table.headers.len() {
+                return Err(ArityError {
+                    expected: table.headers.len(),
+                    actual: row.len(),
+                });
+            }
+            let row: Vec<Cell> = row.into_iter()
+                .enumerate()
+                .map(|(i, cell)| table.admit_cell(i, cell.into()))
+                .collect();
+            let meta = RowMeta::measure(&table.headers, &row, table.config.width_strategy);
+            for (w, cell_width) in table.column_widths.iter_mut().zip(meta.widths.iter()) {
+                *w = core::cmp::max(*w, *cell_width);
+            }
+            count_empty(&mut table.empty_cells, &row);
+            table.rows.push(row);
+            table.meta.push(meta);
+        }
+        Ok(table)
+    }
+
+    /// Append a row.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the row length policy rejects the row, or if the row sets a
+    /// cell under a header the table doesn't have.
+    pub fn row(mut self, row: Row<N>) -> Self {
+        self.insert_admitted(self.rows.len(), row).unwrap_or_else(|e| panic!("{e}"));
+        self
+    }
+
+    /// Like [`row`](Self::row), but returns an error if the row doesn't have
+    /// one cell per column or sets a cell under a header the table doesn't have.
+    pub fn try_row(mut self, row: Row<N>) -> Result<Self, RowError> {
+        self.try_push_row(row)?;
+        Ok(self)
+    }
+
+    /// Append rows of text that are already in shape, moving the strings into
+    /// the table. Cheaper than building a [`Row`] for each when there are
+    /// many rows.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the row length policy rejects a row.
+    pub fn extend_rows(&mut self, rows: Vec<Vec<String>>) {
+        let cells = rows.iter().map(Vec::len).sum();
+        self.rows.reserve(rows.len(), cells);
+        self.meta.reserve(rows.len());
+        let headerless = self.headers.is_empty();
+        for row in rows {
+            let cells = self.fit_row(row.into_iter().map(Cell::Str).collect())
+                .unwrap_or_else(|e| panic!("{e}"));
+            let cells: Vec<Cell> = cells.into_iter()
+                .enumerate()
+                .map(|(i, cell)| self.admit_cell(i, cell))
+                .collect();
+            let meta = RowMeta::measure(&self.headers, &cells, self.config.width_strategy);
+            if self.config.fixed_widths.is_none() {
+                widen(&mut self.column_widths, headerless, &meta.widths);
+            }
+            count_empty(&mut self.empty_cells, &cells);
+            self.rows.push(cells);
+            self.meta.push(meta);
+        }
+    }
+}
+
+/// Each row is added as by [`row`](Table::row), and panics where it would.
+impl<const N: usize> Extend<Row<N>> for Table<ModifyRows, N> {
+    fn extend<I: IntoIterator<Item = Row<N>>>(&mut self, iter: I) {
+        for row in iter {
+            self.insert_admitted(self.rows.len(), row).unwrap_or_else(|e| panic!("{e}"));
+        }
+    }
+}
+
+/// Collecting rows produces a table without headers.
+impl<const N: usize> FromIterator<Row<N>> for Table<ModifyRows, N> {
+    fn from_iter<I: IntoIterator<Item = Row<N>>>(iter: I) -> Self {
+        let mut table = Table::default().end_header();
+        table.extend(iter);
+        table
+    }
+}
+
+/// Owning iterator over the rows of a [`Table`], created by its `into_iter` method.
+pub struct IntoRows {
+    rows: rows::IntoIter,
+}
+
+impl Iterator for IntoRows {
+    type Item = Vec<Cell>;
+
+    fn next(&mut self) -> Option<Vec<Cell>> {
+        self.rows.next()
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.rows.size_hint()
+    }
+}
+
+impl<T, const N: usize> IntoIterator for Table<T, N> {
+    type Item = Vec<Cell>;
+    type IntoIter = IntoRows;
+
+    fn into_iter(self) -> IntoRows {
+        IntoRows {
+            rows: self.rows.into_iter(),
+        }
+    }
+}
+
+/// Shorten `s` to at most `max` columns, ending with an ellipsis if there is
+/// room for one. Escape sequences are dropped from truncated cells.
+fn truncate(s: &str, max: usize, strategy: WidthStrategy) -> String {
+    let bytes = ansi::strip(s);
+    let s = String::from_utf8_lossy(&bytes);
+    let ellipsis = Some(strategy.char_width('…')).filter(|&w| w <= max);
+    let mut out = String::new();
+    let mut used = 0;
+    for c in s.chars() {
+        let w = strategy.char_width(c);
+        if used + w + ellipsis.unwrap_or(0) > max {
+            break;
+        }
+        used += w;
+        out.push(c);
+    }
+    if ellipsis.is_some() {
+        out.push('…');
+    }
+    out
+}
+
+/// `text`, which is `visible` columns wide, with its line breaks escaped,
+/// and its width.
+fn one_line(text: Cow<'_, str>, visible: usize, strategy: WidthStrategy) -> (Cow<'_, str>, usize) {
+    if !text.contains(['\n', '\r']) {
+        return (text, visible);
+    }
+    let text = NewlinePolicy::Escape.replace(&text);
+    let visible = width(&text, strategy);
+    (Cow::Owned(text), visible)
+}
+
+/// Width of a rendered line whose columns are `widths` wide, each followed by
+/// a space.
+fn line_width<I: IntoIterator<Item = usize>>(widths: I) -> usize {
+    widths.into_iter().map(|w| w + 1).sum()
+}
+
+/// Narrow `widths` until a line fits in `total`, cutting each column in
+/// proportion to its width times its weight, and leaving each at least one
+/// wide. Columns of weight 0 are only cut when the others can't give enough.
+fn shrink_widths(widths: &mut [usize], weights: &[u32], total: usize) {
+    let mut excess = line_width(widths.iter().copied()).saturating_sub(total);
+    for weight in [|w: u32| w, |w: u32| core::cmp::max(w, 1)] {
+        while excess > 0 {
+            let shares: Vec<usize> = widths.iter()
+                .zip(weights)
+                .map(|(&width, &w)| if width > 1 { width * weight(w) as usize } else { 0 })
+                .collect();
+            let sum: usize = shares.iter().sum();
+            if sum == 0 {
+                break;
+            }
+            // Rounding the cuts up favours the columns on the left.
+            let mut cut_total = 0;
+            for (width, share) in widths.iter_mut().zip(shares).rev() {
+                let cut = (excess * share).div_ceil(sum).min(*width - 1).min(excess - cut_total);
+                *width -= cut;
+                cut_total += cut;
+            }
+            excess -= cut_total;
+        }
+    }
+}
+
+/// Write `s`, which is `visible` columns wide, padded with spaces to
+/// `target_width` columns, truncating it if it's wider. Only truncation allocates.
+fn write_padded<W: core::fmt::Write>(f: &mut W, s: &str, visible: usize, target_width: usize, alignment: Alignment, strategy: WidthStrategy) -> core::fmt::Result {
+    let truncated;
+    let (s, visible) = match visible {
+        w if w > target_width => {
+            truncated = truncate(s, target_width, strategy);
+            (truncated.as_str(), width(&truncated, strategy))
+        }
+        w => (s, w),
+    };
+    let padding = target_width.saturating_sub(visible);
+    let (left, right) = match alignment {
+        Alignment::Left => (0, padding),
+        Alignment::Right => (padding, 0),
+        Alignment::Center => (padding / 2, padding - padding / 2),
+    };
+    write_spaces(f, left)?;
+    f.write_str(s)?;
+    write_spaces(f, right)
+}
+
+const SPACES: &str = "                                                                ";
+
+/// Write `n` spaces, a chunk at a time, as the formatter can't pad wider
+/// than `u16::MAX`.
+fn write_spaces<W: core::fmt::Write>(f: &mut W, mut n: usize) -> core::fmt::Result {
+    while n > 0 {
+        let chunk = core::cmp::min(n, SPACES.len());
+        f.write_str(&SPACES[..chunk])?;
+        n -= chunk;
+    }
+    Ok(())
+}
+
+/// Lets the renderer write to an `io::Write`, keeping the error it returns.
+#[cfg(feature = "std")]
+struct IoWriter<W: std::io::Write> {
+    inner: W,
+    error: Option<std::io::Error>,
+}
+
+#[cfg(feature = "std")]
+impl<W: std::io::Write> core::fmt::Write for IoWriter<W> {
+    fn write_str(&mut self, s: &str) -> core::fmt::Result {
+        self.inner.write_all(s.as_bytes()).map_err(|e| {
+            self.error = Some(e);
+            core::fmt::Error
+        })
+    }
+}
+
+impl<T, const N: usize> Table<T, N> {
+    /// Render as `Display` does, straight into `writer` through a
+    /// `BufWriter`. Memory use doesn't grow with the size of the table, as
+    /// the rendered text is never held all at once.
+    #[cfg(feature = "std")]
+    pub fn write_to<W: std::io::Write>(&self, writer: W) -> std::io::Result<()> {
+        let mut out = IoWriter {
+            inner: std::io::BufWriter::new(writer),
+            error: None,
+        };
+        if self.write_plain(&mut out).is_err() {
+            return Err(out.error.unwrap_or_else(|| std::io::Error::other("failed to render table")));
+        }
+        std::io::Write::flush(&mut out.inner)
+    }
+
+    /// Render as `Display` does, or explain why the table can't be: a row of
+    /// the wrong length, or a cell with a control character. `Display`
+    /// renders such tables as they are.
+    pub fn try_render(&self) -> Result<String, TableError> {
+        self.check_renderable()?;
+        let mut out = String::new();
+        self.write_plain(&mut out).expect("writing to a String can't fail");
+        Ok(out)
+    }
+
+    fn check_renderable(&self) -> Result<(), TableError> {
+        match self.render_issues().next() {
+            Some(issue) => Err(issue),
+            None => Ok(()),
+        }
+    }
+
+    /// Everything [`try_render`](Self::try_render) would reject, in order.
+    fn render_issues(&self) -> impl Iterator<Item = TableError> + '_ {
+        let reject_ansi = self.config.ansi == AnsiPolicy::Reject;
+        let invalid = move |text: &str| has_control(text) || (reject_ansi && text.contains('\x1b'));
+        self.rows.iter().enumerate().flat_map(move |(i, row)| {
+            let arity = (!self.headers.is_empty() && row.len() != self.headers.len())
+                .then_some(TableError::Arity { row: i, expected: self.headers.len(), actual: row.len() });
+            let content = row.iter()
+                .enumerate()
+                .filter(move |(_, cell)| cell.as_str().is_some_and(invalid))
+                .map(move |(column, _)| TableError::InvalidContent { row: i, column });
+            arity.into_iter().chain(content)
+        })
+    }
+
+    /// Every problem with the table, for tools that should fail before
+    /// printing a broken layout: empty headers, all the ragged rows and
+    /// control characters that [`try_render`](Self::try_render) would stop at
+    /// the first of, and columns too wide for the [`fit_width`](Self::fit_width).
+    pub fn validate(&self) -> Vec<TableError> {
+        let empty_headers = self.headers.iter()
+            .enumerate()
+            .filter(|(_, h)| h.text.trim().is_empty())
+            .map(|(column, _)| TableError::EmptyHeader { column });
+        let max = self.config.fit_width.map(|(total, _)| total.saturating_sub(1));
+        let overflows = self.natural_widths()
+            .into_iter()
+            .enumerate()
+            .filter_map(|(column, width)| max.filter(|max| width > *max).map(|max| TableError::WidthOverflow { column, width, max }));
+        empty_headers.chain(self.render_issues()).chain(overflows).collect()
+    }
+
+    /// Render in a layout meant for golden-file tests, which is kept the same
+    /// across versions of this crate: each column is as wide as its widest
+    /// cell or header, within the column's own `min_width` and `max_width`,
+    /// columns are separated by one space, no line has trailing spaces, and
+    /// every line ends with `\n`. Line breaks kept in cells by
+    /// [`NewlinePolicy::Keep`] are escaped as [`NewlinePolicy::Escape`] would,
+    /// so that each row stays on one line. Settings that change the layout,
+    /// such as [`fit_width`](Self::fit_width), are ignored;
+    /// [`fixed_widths`](Self::fixed_widths) is kept.
+    pub fn render_canonical(&self) -> String {
+        let strategy = self.config.width_strategy;
+        let rows = self.shown_rows().map(move |row| row.map(move |(text, w)| one_line(text, w, strategy)));
+        let widths: Vec<usize> = match &self.config.fixed_widths {
+            Some(fixed) => (0..core::cmp::max(self.column_widths.len(), fixed.len()))
+                .map(|i| fixed.get(i).copied().unwrap_or(DEFAULT_MIN_WIDTH))
+                .collect(),
+            None => {
+                let mut widths: Vec<usize> = (0..self.column_widths.len()).map(|i| self.constrained_width(i, 0)).collect();
+                // Escaping may have widened cells past the measured widths.
+                for row in rows.clone() {
+                    for ((_, w), (i, width)) in row.zip(widths.iter_mut().enumerate()) {
+                        let max = self.headers.get(i).and_then(|h| h.max_width).unwrap_or(usize::MAX);
+                        *width = core::cmp::max(*width, core::cmp::min(w, max));
+                    }
+                }
+                widths
+            }
+        };
+        let mut text = String::new();
+        let mut lines = plain::PlainLines::new(self, rows, self.rows.len(), widths, None);
+        while let Some(line) = lines.write_next(&mut text) {
+            line.expect("writing to a String can't fail");
+        }
+        let mut canonical = String::with_capacity(text.len());
+        for line in text.lines() {
+            canonical.push_str(line.trim_end_matches([' ', '\r']));
+            canonical.push('\n');
+        }
+        canonical
+    }
+
+    /// Render as `Display` does into `buf`, replacing what it held. Reusing one
+    /// buffer across frames keeps its allocation, so a table redrawn many times
+    /// a second doesn't allocate a new `String` each time.
+    pub fn render_into_buffer(&self, buf: &mut String) {
+        buf.clear();
+        self.write_plain(buf).expect("writing to a String can't fail");
+    }
+
+    /// The lines of the `Display` output, without line endings. Each row is
+    /// rendered only when its line is reached, so a pager can take just the
+    /// lines it shows.
+    pub fn lines(&self) -> impl Iterator<Item = String> + '_ {
+        self.plain_lines()
+    }
+
+    /// Number of rows left out of the rendered table by
+    /// [`max_render_rows`](Self::max_render_rows).
+    pub fn omitted_rows(&self) -> usize {
+        self.rows_left_out(self.rows.len())
+    }
+
+    /// How many of `rows` rows are left out by
+    /// [`max_render_rows`](Self::max_render_rows).
+    fn rows_left_out(&self, rows: usize) -> usize {
+        match self.config.max_render_rows {
+            Some(max) => rows.saturating_sub(max),
+            None => 0,
+        }
+    }
+
+    /// The line printed after `rows` rows laid out at `widths`: the empty
+    /// message, or a count of the rows left out.
+    fn footer_for(&self, rows: usize, widths: &[usize]) -> Option<String> {
+        match self.rows_left_out(rows) {
+            0 if rows == 0 => self.empty_footer(widths),
+            0 => None,
+            1 => Some("… 1 more row".to_string()),
+            n => Some(format!("… {n} more rows")),
+        }
+    }
+
+    /// The line printed in place of rows when there are none, under columns
+    /// `widths` wide.
+    fn empty_footer(&self, widths: &[usize]) -> Option<String> {
+        match self.config.empty_table {
+            EmptyTablePolicy::Header => self.config.empty_message.clone(),
+            EmptyTablePolicy::Placeholder => {
+                let message = self.config.empty_message.as_deref().unwrap_or("(no data)");
+                let width = crate::width(message, self.config.width_strategy);
+                // The table is as wide as its lines, without the trailing space.
+                let table_width = line_width(widths.iter().copied()).saturating_sub(1).max(width);
+                let mut line = String::new();
+                write_padded(&mut line, message, width, table_width, Alignment::Center, self.config.width_strategy).expect("writing to a String can't fail");
+                Some(line)
+            }
+            EmptyTablePolicy::Nothing => None,
+        }
+    }
+
+    /// Whether the header is rendered above `rows` rows.
+    fn shows_header(&self, rows: usize) -> bool {
+        let hidden_when_empty = self.config.empty_table == EmptyTablePolicy::Nothing;
+        !self.config.skip_header && !self.headers.is_empty() && (rows > 0 || !hidden_when_empty)
+    }
+
+    /// Render as aligned columns, the format used by `Display`.
+    fn write_plain<W: core::fmt::Write>(&self, f: &mut W) -> core::fmt::Result {
+        let mut lines = self.plain_lines();
+        while let Some(line) = lines.write_next(f) {
+            line?;
+        }
+        Ok(())
+    }
+
+    /// The lines of the plain format, at the widths `Display` uses.
+    fn plain_lines(&self) -> plain::PlainLines<'_, T, N, impl Iterator<Item = impl Iterator<Item = (Cow<'_, str>, usize)>> + Clone + '_> {
+        let widths = self.display_widths();
+        let groups = self.column_groups(&widths);
+        plain::PlainLines::new(self, self.shown_rows(), self.rows.len(), widths, groups)
+    }
+
+    /// The text and width of each cell of the rows that are rendered.
+    fn shown_rows(&self) -> impl Iterator<Item = impl Iterator<Item = (Cow<'_, str>, usize)>> + Clone + '_ {
+        self.rows.iter()
+            .zip(self.meta.iter())
+            .take(self.rows.len() - self.omitted_rows())
+            .map(move |(row, meta)| self.plain_texts(row, &meta.widths))
+    }
+
+    fn display_widths(&self) -> Vec<usize> {
+        let mut widths = self.natural_widths();
+        if let Some((total, OverflowPolicy::Truncate)) = self.config.fit_width {
+            let weights: Vec<u32> = (0..widths.len())
+                .map(|c| self.headers.get(c).and_then(|h| h.shrink_weight).unwrap_or(1))
+                .collect();
+            shrink_widths(&mut widths, &weights, total);
+        }
+        widths
+    }
+
+    /// The column widths before fitting them to the [`fit_width`](Self::fit_width).
+    fn natural_widths(&self) -> Vec<usize> {
+        match &self.config.fixed_widths {
+            Some(fixed) => {
+                let columns = core::cmp::max(self.column_widths.len(), fixed.len());
+                (0..columns)
+                    .map(|i| fixed.get(i).copied().unwrap_or(DEFAULT_MIN_WIDTH))
+                    .collect()
+            }
+            None => (0..self.column_widths.len())
+                .map(|i| self.display_width(i))
+                .collect(),
+        }
+    }
+
+    /// The columns to render together, when the table is wider than its
+    /// [`fit_width`](Self::fit_width) and the policy leaves some out or moves
+    /// them below the rest.
+    fn column_groups(&self, widths: &[usize]) -> Option<Vec<Vec<usize>>> {
+        let (total, policy) = self.config.fit_width?;
+        if line_width(widths.iter().copied()) <= total {
+            return None;
+        }
+        match policy {
+            OverflowPolicy::Truncate => None,
+            OverflowPolicy::DropColumns => {
+                let priority = |c: usize| self.headers.get(c).and_then(|h| h.priority).unwrap_or(0);
+                let mut kept: Vec<usize> = (0..widths.len()).collect();
+                let mut by_priority = kept.clone();
+                by_priority.sort_by_key(|&c| (priority(c), core::cmp::Reverse(c)));
+                for c in by_priority {
+                    if kept.len() == 1 || line_width(kept.iter().map(|&k| widths[k])) <= total {
+                        break;
+                    }
+                    kept.retain(|&k| k != c);
+                }
+                Some(vec![kept])
+            }
+            OverflowPolicy::Wrap => {
+                let mut groups: Vec<Vec<usize>> = Vec::new();
+                let mut used = 0;
+                for (c, w) in widths.iter().enumerate() {
+                    match groups.last_mut() {
+                        Some(group) if used + w < total => group.push(c),
+                        _ => {
+                            groups.push(vec![c]);
+                            used = 0;
+                        }
+                    }
+                    used += w + 1;
+                }
+                Some(groups)
+            }
+        }
+    }
+
+    fn write_plain_header<W: core::fmt::Write>(&self, f: &mut W, widths: &[usize]) -> core::fmt::Result {
+        for (header, width) in self.headers.iter()
+            .zip(widths.iter()) {
+            write_padded(f, &header.text, crate::width(&header.text, self.config.width_strategy), *width, header.alignment, self.config.width_strategy)?;
+            f.write_char(' ')?;
+        }
+        writeln!(f)
+    }
+
+    /// Write a row whose cells are `cell_widths` wide, as measured by `RowMeta`.
+    fn write_plain_row<W: core::fmt::Write>(&self, f: &mut W, row: &[Cell], cell_widths: &[usize], widths: &[usize]) -> core::fmt::Result {
+        self.write_plain_texts(f, self.plain_texts(row, cell_widths), widths)
+    }
+
+    /// The text shown for each cell of a row whose cells are `cell_widths`
+    /// wide, and its visible width.
+    fn plain_texts<'a>(&'a self, row: &'a [Cell], cell_widths: &'a [usize]) -> impl Iterator<Item = (Cow<'a, str>, usize)> + 'a {
+        let mut null_width = None;
+        row.iter().zip(cell_widths).enumerate().map(move |(i, (cell, w))| match cell {
+            Cell::Empty => {
+                let null = self.config.null_text.as_str();
+                (Cow::Borrowed(null), *null_width.get_or_insert_with(|| crate::width(null, self.config.width_strategy)))
+            }
+            cell if self.config.ansi == AnsiPolicy::Reject => (Cow::Owned(ansi::strip_str(&self.shown_text(i, cell)).into_owned()), *w),
+            cell => (self.shown_text(i, cell), *w),
+        })
+    }
+
+    /// The text shown for `cell` in column `col`, through the column's format.
+    fn shown_text<'c>(&self, col: usize, cell: &'c Cell) -> Cow<'c, str> {
+        shown_text(self.headers.get(col), cell)
+    }
+
+    /// `cell` as shown in column `col`: a text cell if the column has a
+    /// format, or else the cell itself.
+    fn shown_cell(&self, col: usize, cell: &Cell) -> Cell {
+        match self.headers.get(col).and_then(|h| h.format.as_ref()) {
+            Some(_) if !matches!(cell, Cell::Empty) => self.shown_text(col, cell).into_owned().into(),
+            _ => cell.clone(),
+        }
+    }
+
+    /// Write a row given the rendered text and visible width of each cell.
+    fn write_plain_texts<'c, W: core::fmt::Write, I: IntoIterator<Item = (Cow<'c, str>, usize)>>(&self, f: &mut W, texts: I, widths: &[usize]) -> core::fmt::Result {
+        for (i, ((text, visible), width)) in texts.into_iter()
+            .zip(widths.iter())
+            .enumerate() {
+            write_padded(f, &text, visible, *width, self.cell_alignment(i), self.config.width_strategy)?;
+            f.write_char(' ')?;
+        }
+        writeln!(f)
+    }
+
+    /// The width column `col` is rendered at, after applying the header's
+    /// width constraints.
+    fn display_width(&self, col: usize) -> usize {
+        self.constrained_width(col, DEFAULT_MIN_WIDTH)
+    }
+
+    /// The width of column `col` within the header's constraints, or at least
+    /// `default_min` if the header doesn't set a minimum.
+    fn constrained_width(&self, col: usize, default_min: usize) -> usize {
+        let header = self.headers.get(col);
+        let min = header.and_then(|h| h.min_width).unwrap_or(default_min);
+        let mut width = core::cmp::max(self.column_widths[col], min);
+        if !self.config.null_text.is_empty() && self.empty_cells.get(col).is_some_and(|&count| count > 0) {
+            width = core::cmp::max(width, crate::width(&self.config.null_text, self.config.width_strategy));
+        }
+        match header.and_then(|h| h.max_width) {
+            Some(max) => core::cmp::min(width, max),
+            None => width,
+        }
+    }
+
+    fn cell_alignment(&self, col: usize) -> Alignment {
+        let header = self.headers.get(col);
+        header.and_then(|h| h.cell_alignment)
+            .or_else(|| header.and_then(|h| h.column_type).map(|t| t.default_alignment()))
+            .unwrap_or(self.config.default_alignment)
+    }
+}
+
+/// A table that hasn't had any rows added prints just its header.
+impl<T, const N: usize> core::fmt::Display for Table<T, N> {
+    fn fmt(&self, f: &mut core::fmt::Formatter) -> core::fmt::Result {
+        self.write_plain(f)
+    }
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Row<const N: usize> {
+    cells: Vec<Cell>,
+    /// Preferred alignments of `ToCell` values, by cell index.
+    alignments: Vec<(usize, Alignment)>,
+    /// Cells set by header text, placed when the row is added to a table.
+    named: Vec<(String, Cell)>,
+    tag: Option<RowTag>,
+}
+
+impl<const N: usize> Row<N> {
+    pub fn new() -> Self {
+        Row {
+            cells: Vec::new(),
+            alignments: Vec::new(),
+            named: Vec::new(),
+            tag: None,
+        }
+    }
+
+    /// Start a row with room for `cols` cells.
+    pub fn with_capacity(cols: usize) -> Self {
+        Row {
+            cells: Vec::with_capacity(cols),
+            ..Row::new()
+        }
+    }
+
+    pub fn cell<C: Into<Cell>>(mut self, cell: C) -> Row<N> {
+        self.cells.push(cell.into());
+        self
+    }
+
+    /// Set the cell under the header named `header`, in any order. Columns
+    /// that aren't set are empty. Adding the row to a table without that
+    /// header panics, or returns [`RowError::UnknownColumn`] from the `try_`
+    /// methods.
+    pub fn set<C: Into<Cell>>(mut self, header: &str, cell: C) -> Row<N> {
+        self.named.push((header.to_string(), cell.into()));
+        self
+    }
+
+    /// Attach metadata to the row, such as an ID or a severity. The tag isn't
+    /// rendered, but can be looked up with [`Table::row_tag`] and used to find
+    /// and filter rows.
+    pub fn tag<V: Any + Send + Sync>(mut self, value: V) -> Row<N> {
+        self.tag = Some(RowTag(Arc::new(value)));
+        self
+    }
+
+    /// Add a cell from a type implementing [`ToCell`].
+    pub fn value<V: ToCell + ?Sized>(mut self, value: &V) -> Row<N> {
+        if let Some(alignment) = value.alignment() {
+            self.alignments.push((self.cells.len(), alignment));
+        }
+        let cell = match value.style() {
+            Some(style) => style.apply(&value.to_cell().text()).into(),
+            None => value.to_cell(),
+        };
+        self.cell(cell)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// The rendered text of every cell, for comparing against literals.
+    pub(crate) fn texts<T, const N: usize>(table: &Table<T, N>) -> Vec<Vec<String>> {
+        table.rows()
+            .map(|row| row.iter().map(|c| c.to_string()).collect())
+            .collect()
+    }
+
+    #[test]
+    fn it_works() {
+        let table = Table::new()
+            .header("Name")
+            .header("Age")
+            .row(Row::new().cell("Alice").cell("20"))
+            .row(Row::new().cell("Bob").cell("30"));
+        println!("{}", table);
+        assert_eq!(table.to_string(),
+                   "Name     Age      \n".to_owned() +
+                       "Alice    20       \n" +
+                       "Bob      30       \n");
+    }
+
+    #[test]
+    fn test_transpose() {
+        let table = Table::new()
+            .header("Name")
+            .header("Age")
+            .row(Row::new().cell("Alice").cell("20"))
+            .row(Row::new().cell("Bob").cell("30"))
+            .transpose();
+        assert_eq!(table.to_string(),
+                   "Name     Alice    Bob      \n".to_owned() +
+                       "Age      20       30       \n");
+    }
+
+    #[test]
+    fn test_pivot() {
+        let table = Table::new()
+            .header("Region")
+            .header("Product")
+            .header("Sales")
+            .row(Row::new().cell("East").cell("Apples").cell("10"))
+            .row(Row::new().cell("West").cell("Apples").cell("5"))
+            .row(Row::new().cell("East").cell("Pears").cell("3"))
+            .row(Row::new().cell("East").cell("Apples").cell("2"));
+        let pivot = table.pivot(0, 1, 2, Aggregation::Sum);
+        assert_eq!(pivot.to_string(),
+                   "Region   Apples   Pears    \n".to_owned() +
+                       "East     12       3        \n" +
+                       "West     5                 \n");
+        let pivot = table.pivot(0, 1, 2, Aggregation::Count);
+        assert_eq!(texts(&pivot)[1], vec!["West", "1", "0"]);
+        let big = Table::new()
+            .header("Region")
+            .header("Product")
+            .header("Sales")
+            .row(Row::new().cell("East").cell("Apples").cell(i64::MAX))
+            .row(Row::new().cell("East").cell("Apples").cell(i64::MAX));
+        assert_eq!(big.pivot(0, 1, 2, Aggregation::Sum).get(0, 1), Some(&Cell::Float(2.0 * i64::MAX as f64)));
+    }
+
+    #[test]
+    fn test_group_by() {
+        let table: Table<ModifyRows> = Table::new()
+            .header("Team")
+            .header("Name")
+            .header("Points")
+            .row(Row::new().cell("Red").cell("Alice").cell("3"))
+            .row(Row::new().cell("Blue").cell("Bob").cell("4"))
+            .row(Row::new().cell("Red").cell("Carol").cell("5"));
+        let grouped = table.group_by(0, &[(1, Aggregation::Count), (2, Aggregation::Sum)]);
+        assert_eq!(texts(&grouped), vec![vec!["Red", "2", "8"], vec!["Blue", "1", "4"]]);
+        let sections = table.group_by_subtotals(0, &[(2, Aggregation::Sum)]);
+        assert_eq!(texts(&sections), vec![
+            vec!["Red", "Alice", "3"],
+            vec!["Red", "Carol", "5"],
+            vec!["Red total", "", "8"],
+            vec!["Blue", "Bob", "4"],
+            vec!["Blue total", "", "4"],
+        ]);
+    }
+
+    #[test]
+    fn test_column_stats() {
+        let table: Table<ModifyRows> = Table::new()
+            .header("Name")
+            .header("Age")
+            .row(Row::new().cell("Alice").cell("20"))
+            .row(Row::new().cell("Bob").cell("n/a"))
+            .row(Row::new().cell("Carol").cell("40"));
+        assert_eq!(table.column_stats(1), Some(ColumnStats { count: 2, min: 20.0, max: 40.0, mean: 30.0 }));
+        assert_eq!(table.column_stats(0), None);
+    }
+
+    #[test]
+    fn test_get_set() {
+        let mut table: Table<ModifyRows> = Table::new()
+            .header("Name")
+            .header("Password")
+            .row(Row::new().cell("Alice").cell("correct horse battery staple"));
+        assert_eq!(table.get(0, 1), Some(&"correct horse battery staple".into()));
+        assert_eq!(table.get(1, 0), None);
+        assert_eq!(table.set(0, 1, "***"), Some("correct horse battery staple".into()));
+        assert_eq!(table.set(0, 2, "***"), None);
+        assert_eq!(table.get(0, 1), Some(&"***".into()));
+        assert_eq!(table.column_widths, vec![5, 8]);
+        assert_eq!(table.set_by_name(0, "Name", "Alicia"), Some("Alice".into()));
+        assert_eq!(table.set_by_name(0, "Email", "a@example.com"), None);
+        assert_eq!(table.get(0, 0), Some(&"Alicia".into()));
+    }
+
+    #[test]
+    fn test_insert_remove_row() {
+        let mut table: Table<ModifyRows> = Table::new()
+            .header("Name")
+            .row(Row::new().cell("Alice"))
+            .row(Row::new().cell("Bob"));
+        table.insert_row(1, Row::new().cell("Bartholomew"));
+        assert_eq!(texts(&table), vec![vec!["Alice"], vec!["Bartholomew"], vec!["Bob"]]);
+        assert_eq!(table.column_widths, vec![11]);
+        assert_eq!(table.remove_row(1), vec!["Bartholomew"]);
+        assert_eq!(table.column_widths, vec![5]);
+    }
+
+    #[test]
+    fn test_add_column() {
+        let mut table: Table<ModifyRows> = Table::new()
+            .header("Before")
+            .header("After")
+            .row(Row::new().cell("10").cell("15"))
+            .row(Row::new().cell("20").cell("10"));
+        let delta = table.rows.iter()
+            .map(|r| {
+                let (a, b) = (r[0].as_f64().unwrap(), r[1].as_f64().unwrap());
+                format!("{:+.0}%", (b - a) / a * 100.0)
+            })
+            .collect();
+        table.add_column("Δ%", delta).unwrap();
+        table.add_column_with_default("Notes", "-");
+        assert_eq!(texts(&table)[1], vec!["20", "10", "-50%", "-"]);
+        assert_eq!(table.add_column("Oops", Vec::<Cell>::new()), Err(ColumnLengthError { expected: 2, actual: 0 }));
+        assert_eq!(table.headers.len(), 4);
+    }
+
+    #[test]
+    fn test_add_column_headerless() {
+        let mut table = Table::headerless(2).row(Row::new().cell("a").cell("b"));
+        table.add_column("X", vec!["c"]).unwrap();
+        assert_eq!(texts(&table), vec![vec!["a", "b", "c"]]);
+        assert_eq!(table.to_string(), "                  X        \na        b        c        \n");
+    }
+
+    #[test]
+    fn test_remove_column() {
+        let mut table: Table<ModifyRows> = Table::new()
+            .header("Name")
+            .header("Timestamp")
+            .header("Age")
+            .row(Row::new().cell("Alice").cell("2023-01-01T00:00:00Z").cell("20"));
+        assert_eq!(table.remove_column("Timestamp").map(|h| h.text).as_deref(), Some("Timestamp"));
+        assert!(table.remove_column("Timestamp").is_none());
+        assert!(table.remove_column(2).is_none());
+        assert_eq!(table.to_string(),
+                   "Name     Age      \n".to_owned() +
+                       "Alice    20       \n");
+    }
+
+    #[test]
+    fn test_rename_header() {
+        let mut table: Table<ModifyRows> = Table::new()
+            .header("created_at_timestamp")
+            .row(Row::new().cell("2023"));
+        assert_eq!(table.rename_header("created_at_timestamp", "Created").as_deref(), Some("created_at_timestamp"));
+        assert_eq!(table.rename_header(1, "Nope"), None);
+        assert_eq!(table.headers[0].text, "Created");
+        assert_eq!(table.column_widths, vec![7]);
+    }
+
+    #[test]
+    fn test_row_iteration() {
+        let table: Table<ModifyRows> = Table::new()
+            .header("Name")
+            .row(Row::new().cell("Alice"))
+            .row(Row::new().cell("Bob"));
+        let names: Vec<&str> = table.rows().filter_map(|r| r[0].as_str()).collect();
+        assert_eq!(names, vec!["Alice", "Bob"]);
+        let owned: Vec<Vec<Cell>> = table.into_iter().collect();
+        assert_eq!(owned, vec![vec!["Alice"], vec!["Bob"]]);
+    }
+
+    #[test]
+    fn test_column_iteration() {
+        let table: Table<ModifyRows> = Table::new()
+            .header("Name")
+            .header("Age")
+            .row(Row::new().cell("Alice").cell("20"))
+            .row(Row::new().cell("Bob"));
+        assert_eq!(table.column("Age").unwrap().collect::<Vec<_>>(), vec![&Cell::from("20"), &Cell::Empty]);
+        assert_eq!(table.column(0).unwrap().collect::<Vec<_>>(), vec![&Cell::from("Alice"), &Cell::from("Bob")]);
+        assert!(table.column("Height").is_none());
+    }
+
+    #[test]
+    fn test_collect_and_extend() {
+        let people = [("Alice", 20), ("Bob", 30)];
+        let table: Table<ModifyRows> = people.iter()
+            .map(|(name, age)| Row::new().cell(*name).cell(*age))
+            .collect();
+        assert_eq!(table.to_string(),
+                   "Alice    20       \n".to_owned() +
+                       "Bob      30       \n");
+
+        let mut table = Table::new()
+            .header("Name")
+            .header("Age")
+            .end_header();
+        table.extend(people.iter().map(|(name, age)| Row::new().cell(*name).cell(*age)));
+        assert_eq!(table.to_string(),
+                   "Name     Age      \n".to_owned() +
+                       "Alice    20       \n" +
+                       "Bob      30       \n");
+    }
+
+    #[test]
+    fn test_clone_eq() {
+        let table: Table<ModifyRows> = Table::new()
+            .header("Name")
+            .row(Row::new().cell("Alice"));
+        let mut copy = table.clone();
+        assert_eq!(table, copy);
+        copy.set(0, 0, "Bob");
+        assert_ne!(table, copy);
+    }
+
+    #[test]
+    fn test_try_row() {
+        let table = Table::<ModifyHeader>::new()
+            .header("Name")
+            .header("Age")
+            .try_row(Row::new().cell("Alice").cell("20"))
+            .unwrap();
+        let err = table.clone().try_row(Row::new().cell("Bob")).unwrap_err();
+        assert_eq!(err, RowError::Arity(ArityError { expected: 2, actual: 1 }));
+        let mut table = table.try_row(Row::new().cell("Bob").cell("30")).unwrap();
+        assert!(table.try_push_row(Row::new().cell("Carol").cell("40").cell("extra")).is_err());
+        assert_eq!(table.row_count(), 2);
+        assert!(Table::<ModifyHeader>::new().header("Name").try_row(Row::new()).is_err());
+    }
+
+    #[test]
+    fn test_row_length_policy() {
+        let table = Table::<ModifyHeader>::new()
+            .header("Name")
+            .header("Age")
+            .row(Row::new().cell("Alice"))
+            .row(Row::new().cell("Bob").cell("30").cell("extra"));
+        assert_eq!(texts(&table), vec![vec!["Alice", ""], vec!["Bob", "30"]]);
+
+        let mut table = Table::<ModifyHeader>::new()
+            .header("Name")
+            .row_length_policy(RowLengthPolicy::PadWithEmpty)
+            .end_header();
+        table.insert_row(0, Row::new());
+        assert_eq!(texts(&table), vec![vec![""]]);
+        assert!(table.try_push_row(Row::new().cell("a").cell("b")).is_err());
+
+        let table = Table::<ModifyHeader>::new()
+            .header("Name")
+            .row_length_policy(RowLengthPolicy::GrowHeaders)
+            .row(Row::new().cell("Alice"))
+            .row(Row::new().cell("Bob").cell("30"));
+        assert_eq!(table.headers.len(), 2);
+        assert_eq!(texts(&table), vec![vec!["Alice", ""], vec!["Bob", "30"]]);
+    }
+
+    #[test]
+    #[should_panic(expected = "row has 1 cells, but the table has 2 columns")]
+    fn test_row_length_policy_error() {
+        let _ = Table::<ModifyHeader>::new()
+            .header("Name")
+            .header("Age")
+            .row_length_policy(RowLengthPolicy::Error)
+            .row(Row::new().cell("Alice"));
+    }
+
+    #[test]
+    fn test_without_header() {
+        let table = Table::new()
+            .header("Name")
+            .row(Row::new().cell("Alice"))
+            .without_header();
+        assert_eq!(table.to_string(), "Alice    \n");
+
+        let mut table = Table::new()
+            .row(Row::new().cell("Bob").cell("30"));
+        assert_eq!(table.to_string(), "Bob      30       \n");
+        table.set_skip_header(false);
+        assert_eq!(table.to_string(), "Bob      30       \n");
+    }
+
+    #[test]
+    fn test_append() {
+        let mut east: Table<ModifyRows> = Table::new()
+            .header("Host")
+            .row(Row::new().cell("east-1"));
+        let west: Table<ModifyRows> = Table::new()
+            .header("Host")
+            .row(Row::new().cell("west-1-long"));
+        east.append(west).unwrap();
+        assert_eq!(texts(&east), vec![vec!["east-1"], vec!["west-1-long"]]);
+        assert_eq!(east.column_widths, vec![11]);
+
+        let other: Table<ModifyRows> = Table::new().header("Name").end_header();
+        let err = east.append(other).unwrap_err();
+        assert_eq!(err.actual, vec!["Name"]);
+    }
+
+    #[test]
+    fn test_dedup() {
+        let mut table: Table<ModifyRows> = Table::new()
+            .header("Host")
+            .header("Region")
+            .row(Row::new().cell("a").cell("east"))
+            .row(Row::new().cell("b").cell("west"))
+            .row(Row::new().cell("a").cell("east"))
+            .row(Row::new().cell("c").cell("east"));
+        table.dedup_rows();
+        assert_eq!(table.row_count(), 3);
+        table.dedup_by_column("Region");
+        assert_eq!(texts(&table), vec![vec!["a", "east"], vec!["b", "west"]]);
+    }
+
+    #[test]
+    fn test_chunks() {
+        let table: Table<ModifyRows> = (0..5)
+            .map(|i| Row::new().cell(i))
+            .collect();
+        let sizes: Vec<usize> = table.chunks(2).map(|t| t.row_count()).collect();
+        assert_eq!(sizes, vec![2, 2, 1]);
+    }
+
+    #[test]
+    fn test_column_builder() {
+        let table = Table::new()
+            .header(Column::new("Name").max_width(6))
+            .header(Column::new("Price").align(Alignment::Right).min_width(6).format(|v| format!("{v}.00")))
+            .row(Row::new().cell("Bartholomew").cell("3"))
+            .row(Row::new().cell("Al").cell("12"));
+        assert_eq!(table.get(1, 1), Some(&"12".into()));
+        assert_eq!(table.to_string(),
+                   "Name    Price \n".to_owned() +
+                       "Barth…   3.00 \n" +
+                       "Al      12.00 \n");
+    }
+
+    #[test]
+    fn test_format_keeps_values() {
+        let mut table = Table::new()
+            .header("Item")
+            .header(Column::new("Cents").kind(ColumnType::Int).format(|v| format!("${v}/100")))
+            .row(Row::new().cell("tea").cell(250))
+            .row(Row::new().cell("water").cell(None::<i64>))
+            .row(Row::new().cell("coffee").cell(90))
+            .null_text("-");
+        assert_eq!(table.get(0, 1).and_then(Cell::as_i64), Some(250));
+        table.sort_by_column("Cents");
+        assert_eq!(table.to_string(),
+                   "Item     Cents    \n".to_owned() +
+                       "coffee    $90/100 \n" +
+                       "tea      $250/100 \n" +
+                       "water           - \n");
+        assert_eq!(table.render(OutputFormat::Json), "[{\"Item\":\"coffee\",\"Cents\":90},{\"Item\":\"tea\",\"Cents\":250},{\"Item\":\"water\",\"Cents\":null}]\n");
+    }
+
+    #[test]
+    fn test_typed_columns() {
+        let mut table = Table::new()
+            .header("Name")
+            .header(Column::new("Size").kind(ColumnType::Int))
+            .row(Row::new().cell("a").cell("100"))
+            .row(Row::new().cell("b").cell("9"))
+            .row(Row::new().cell("c").cell("?"));
+        table.sort_by_column("Size");
+        assert_eq!(table.to_string(),
+                   "Name     Size     \n".to_owned() +
+                       "b               9 \n" +
+                       "a             100 \n" +
+                       "c               ? \n");
+    }
+
+    #[test]
+    fn test_cell_values() {
+        let mut table: Table<ModifyRows> = Table::new()
+            .header("Name")
+            .header("Count")
+            .header("Ratio")
+            .header("Active")
+            .row(Row::new().cell("a").cell(10).cell(0.5).cell(true))
+            .row(Row::new().cell("b").cell(9).cell(1.25).cell(false))
+            .row(Row::new().cell("c").cell(None::<i64>).cell(2.0).cell(true));
+        table.sort_by_column("Count");
+        assert_eq!(texts(&table), vec![
+            vec!["b", "9", "1.25", "false"],
+            vec!["a", "10", "0.5", "true"],
+            vec!["c", "", "2", "true"],
+        ]);
+        let totals = table.group_by(3, &[(1, Aggregation::Sum), (2, Aggregation::Sum)]);
+        assert_eq!(totals.get(1, 1), Some(&Cell::Int(10)));
+        assert_eq!(totals.get(1, 2), Some(&Cell::Float(2.5)));
+    }
+
+    #[test]
+    fn test_styled_value() {
+        struct Status(bool);
+
+        impl ToCell for Status {
+            fn to_cell(&self) -> Cell {
+                if self.0 { "up" } else { "down" }.into()
+            }
+
+            fn style(&self) -> Option<Style> {
+                Some(Style::new().fg(if self.0 { Color::Green } else { Color::Red }))
+            }
+        }
+
+        let table = Table::new()
+            .header("Status")
+            .row(Row::new().value(&Status(false)));
+        assert_eq!(table.get(0, 0), Some(&"\x1b[31mdown\x1b[0m".into()));
+        assert_eq!(table.to_string(), "Status   \n\x1b[31mdown\x1b[0m     \n");
+    }
+
+    #[cfg(feature = "derive")]
+    #[test]
+    fn test_derive_tabular() {
+        #[derive(Tabular)]
+        struct Server {
+            name: String,
+            #[tabular(rename = "CPU", align = "right")]
+            cpu: f32,
+            #[tabular(skip)]
+            #[allow(dead_code)]
+            secret: String,
+        }
+
+        let servers = vec![
+            Server { name: "web-1".to_string(), cpu: 0.5, secret: String::new() },
+            Server { name: "db-1".to_string(), cpu: 12.25, secret: String::new() },
+        ];
+        let table: Table<ModifyRows> = Table::from_iter(servers);
+        let expected = table.clone();
+        assert_eq!(table.to_string(),
+                   "name          CPU \n".to_owned() +
+                       "web-1         0.5 \n" +
+                       "db-1        12.25 \n");
+        let servers = vec![
+            Server { name: "web-1".to_string(), cpu: 0.5, secret: String::new() },
+            Server { name: "db-1".to_string(), cpu: 12.25, secret: String::new() },
+        ];
+        assert_eq!(Table::from_records(servers), expected);
+
+        #[derive(Tabular)]
+        #[tabular(rename_all = "Title Case")]
+        struct Disk {
+            mount_point: &'static str,
+            #[tabular(rename = "Used %")]
+            used_percent: u8,
+        }
+
+        let table: Table<ModifyRows> = Table::from_iter(vec![Disk { mount_point: "/", used_percent: 40 }]);
+        assert_eq!(table.headers().iter().map(|h| h.text.as_str()).collect::<Vec<_>>(), vec!["Mount Point", "Used %"]);
+    }
+
+    #[test]
+    fn test_from_rows() {
+        let table: Table<ModifyRows> = Table::from_rows(
+            vec!["Name", "Age"],
+            vec![vec!["Alice", "20"], vec!["Bob", "30"]],
+        ).unwrap();
+        assert_eq!(table.to_string(),
+                   "Name     Age      \n".to_owned() +
+                       "Alice    20       \n" +
+                       "Bob      30       \n");
+        let err = Table::<ModifyRows>::from_rows(vec!["Name", "Age"], vec![vec!["Alice"]]).unwrap_err();
+        assert_eq!(err, ArityError { expected: 2, actual: 1 });
+    }
+
+    #[test]
+    fn test_display_header_only() {
+        let table = Table::<ModifyHeader>::new().header("Name").header("Size");
+        assert_eq!(table.to_string(), "Name     Size     \n");
+        let table = table.empty_message("(no files)");
+        assert_eq!(table.to_string(), "Name     Size     \n(no files)\n");
+    }
+
+    #[test]
+    fn test_empty_table_policy() {
+        let table = || Table::new().header("Name").header("Size").end_header();
+        assert_eq!(table().empty_table(EmptyTablePolicy::Placeholder).to_string(),
+                   "Name     Size     \n    (no data)    \n");
+        assert_eq!(table().empty_table(EmptyTablePolicy::Nothing).to_string(), "");
+        assert_eq!(table().empty_table(EmptyTablePolicy::Nothing).row(Row::new().cell("a").cell(1)).to_string(),
+                   "Name     Size     \na        1        \n");
+    }
+
+    #[test]
+    fn test_render_canonical() {
+        let table = Table::new()
+            .header("Id")
+            .header(Column::new("Price").align(Alignment::Right))
+            .header("Note")
+            .row(Row::new().cell(1).cell("9.50").cell("on sale"))
+            .row(Row::new().cell(22).cell("120.00").cell(""))
+            .row(Row::new().cell(3).cell("1.00").cell("a\r\nb"));
+        assert_eq!(table.render_canonical(), "Id  Price Note\n1    9.50 on sale\n22 120.00\n3    1.00 a\\r\\nb\n");
+        let table = Table::new().header("A").header("B").row(Row::new().cell("a\nb\nc").cell("x"));
+        assert_eq!(table.render_canonical(), "A       B\na\\nb\\nc x\n");
+        let empty = Table::new().header("Name").header("Size").end_header().empty_table(EmptyTablePolicy::Placeholder);
+        assert_eq!(empty.render_canonical(), "Name Size\n(no data)\n");
+        let empty = Table::new().header("Name").header("Total size").end_header().empty_table(EmptyTablePolicy::Placeholder);
+        assert_eq!(empty.render_canonical(), "Name Total size\n   (no data)\n");
+    }
+
+    #[test]
+    fn test_display_const_n() {
+        let table = Table::<ModifyHeader, 2>::default()
+            .header("Key")
+            .row(Row::new().cell("a"));
+        assert_eq!(table.to_string(), "Key      \na        \n");
+    }
+
+    #[test]
+    fn test_headerless() {
+        let table = Table::headerless(2)
+            .row(Row::new().cell("a").cell("b").cell("dropped"))
+            .row(Row::new().cell("longer"));
+        assert_eq!(texts(&table), vec![vec!["a", "b"], vec!["longer", ""]]);
+        assert_eq!(table.to_string(), "a        b        \nlonger            \n");
+        assert!(Table::headerless(2).try_row(Row::new().cell("a")).is_err());
+
+        let table = Table::headerless(0)
+            .row(Row::new().cell(1).cell(2))
+            .row(Row::new().cell(3));
+        assert_eq!(texts(&table), vec![vec!["1", "2"], vec!["3", ""]]);
+    }
+
+    #[test]
+    fn test_headerless_try_row_after_remove() {
+        let mut table = Table::headerless(2).row(Row::new().cell("a").cell("b"));
+        table.remove_row(0);
+        table.try_push_row(Row::new().cell("c").cell("d")).unwrap();
+        let table = Table::headerless(2)
+            .fixed_widths(&[4, 4])
+            .row(Row::new().cell("a").cell("b"))
+            .try_row(Row::new().cell("c").cell("d"))
+            .unwrap();
+        assert_eq!(texts(&table), vec![vec!["a", "b"], vec!["c", "d"]]);
+        assert!(table.try_row(Row::new().cell("e")).is_err());
+    }
+
+    #[test]
+    fn test_row_set_by_name() {
+        let table: Table<ModifyRows> = Table::new()
+            .header("Host")
+            .header("Status")
+            .header("Notes")
+            .row(Row::new().set("Status", "up").set("Host", "web-1"))
+            .row(Row::new().cell("db-1").set("Notes", "replica"));
+        assert_eq!(texts(&table), vec![vec!["web-1", "up", ""], vec!["db-1", "", "replica"]]);
+        let mut table = table;
+        let err = table.try_push_row(Row::new().set("Owner", "ops")).unwrap_err();
+        assert_eq!(err, RowError::UnknownColumn("Owner".to_string()));
+        assert_eq!(table.row_count(), 2);
+    }
+
+    #[test]
+    fn test_add_column_data() {
+        let mut table = Table::new().end_header();
+        table.add_column_data("Host", vec!["web-1", "db-1"]).unwrap();
+        table.add_column_data("Cores", vec![4, 16]).unwrap();
+        assert_eq!(table.add_column_data("Up", vec![true]), Err(ColumnLengthError { expected: 2, actual: 1 }));
+        assert_eq!(texts(&table), vec![vec!["web-1", "4"], vec!["db-1", "16"]]);
+    }
+
+    #[test]
+    fn test_find_rows() {
+        let table: Table<ModifyRows> = Table::new()
+            .header("Host")
+            .header("Status")
+            .row(Row::new().cell("web-1").cell("up"))
+            .row(Row::new().cell("web-2").cell("down"))
+            .row(Row::new().cell("db-1").cell("down"));
+        assert_eq!(table.find_rows(|cells| cells[1] == "down"), vec![1, 2]);
+        assert!(table.find_rows(|cells| cells[0] == "cache").is_empty());
+    }
+
+    #[test]
+    fn test_paginate() {
+        let mut table: Table<ModifyRows> = Table::new().header("N").end_header();
+        table.extend((1..=5).map(|n| Row::new().cell(n)));
+        let pages: Vec<String> = table.paginate(2).collect();
+        assert_eq!(pages.len(), 3);
+        assert_eq!(pages[1], "N        \n3        \n4        \npage 2/3\n");
+        assert_eq!(pages[2], "N        \n5        \npage 3/3\n");
+        assert_eq!(Table::new().header("N").end_header().paginate(10).collect::<Vec<_>>(), vec!["N        \npage 1/1\n"]);
+    }
+
+    #[test]
+    fn test_render_new_rows() {
+        let mut table: Table<ModifyRows> = Table::new()
+            .header("Step")
+            .row(Row::new().cell("fetch"));
+        assert_eq!(table.render_new_rows(), "Step     \nfetch    \n");
+        assert_eq!(table.render_new_rows(), "");
+        table.extend([Row::new().cell("build"), Row::new().cell("deploy to production")]);
+        assert_eq!(table.render_new_rows(), "build    \ndeploy … \n");
+    }
+
+    #[test]
+    fn test_sort_by_columns() {
+        let mut table: Table<ModifyRows> = Table::new()
+            .header("Namespace")
+            .header("Name")
+            .header(Column::new("Restarts").kind(ColumnType::Int))
+            .row(Row::new().cell("kube").cell("dns").cell(2))
+            .row(Row::new().cell("app").cell("web").cell(0))
+            .row(Row::new().cell("kube").cell("proxy").cell(9))
+            .row(Row::new().cell("app").cell("api").cell(0));
+        table.sort_by_columns(&[("Namespace", SortOrder::Ascending), ("Restarts", SortOrder::Descending)]);
+        let names: Vec<String> = table.column("Name").unwrap().map(|c| c.to_string()).collect();
+        assert_eq!(names, vec!["web", "api", "proxy", "dns"]);
+    }
+
+    #[test]
+    fn test_reverse_and_descending() {
+        let mut table: Table<ModifyRows> = Table::new()
+            .header("Id")
+            .header("Size")
+            .row(Row::new().cell(1).cell(10))
+            .row(Row::new().cell(2).cell(30))
+            .row(Row::new().cell(3).cell(10));
+        table.reverse_rows();
+        assert_eq!(texts(&table)[0], vec!["3", "10"]);
+        table.sort_by_column_descending("Size");
+        let ids: Vec<String> = table.column("Id").unwrap().map(|c| c.to_string()).collect();
+        assert_eq!(ids, vec!["2", "3", "1"]);
+    }
+
+    #[test]
+    fn test_distinct() {
+        let table: Table<ModifyRows> = Table::new()
+            .header("Status")
+            .row(Row::new().cell("running"))
+            .row(Row::new().cell("failed"))
+            .row(Row::new().cell("running"));
+        assert_eq!(table.distinct("Status"), Some(vec![("running".into(), 2), ("failed".into(), 1)]));
+        assert_eq!(table.distinct("Owner"), None);
+    }
+
+    #[test]
+    fn test_take_skip() {
+        let table: Table<ModifyRows> = Table::new()
+            .header("Id")
+            .row(Row::new().cell(1))
+            .row(Row::new().cell(2))
+            .row(Row::new().cell("a much longer id"));
+        assert_eq!(texts(&table.clone().skip(1).take(1)), vec![vec!["2"]]);
+        assert_eq!(table.clone().take(2).column_widths, vec![2]);
+        assert!(table.skip(5).is_empty());
+    }
+
+    #[test]
+    fn test_row_tags() {
+        #[derive(Debug, PartialEq)]
+        enum Severity { Low, High }
+
+        let mut table = Table::new()
+            .header("Check")
+            .row(Row::new().cell("disk").tag(Severity::Low))
+            .row(Row::new().cell("cpu"))
+            .row(Row::new().cell("memory").tag(Severity::High));
+        assert_eq!(table.to_string(), "Check    \ndisk     \ncpu      \nmemory   \n");
+        assert_eq!(table.row_tag::<Severity>(2), Some(&Severity::High));
+        assert_eq!(table.row_tag::<u32>(2), None);
+        assert_eq!(table.row_tag::<Severity>(1), None);
+
+        table.sort_by_column(0);
+        assert_eq!(table.find_rows_by_tag(|s: &Severity| *s == Severity::High), vec![2]);
+        table.set_row_tag(0, Severity::High);
+        let high = table.filter_by_tag(|s: &Severity| *s == Severity::High);
+        assert_eq!(texts(&high), vec![vec!["cpu"], vec!["memory"]]);
+    }
+
+    #[test]
+    fn test_interned_column() {
+        let table = Table::new()
+            .header("Host")
+            .header(Column::new("Region").intern())
+            .row(Row::new().cell("web-1").cell("us-east"))
+            .row(Row::new().cell("web-2").cell("us-east"))
+            .row(Row::new().cell("db-1").cell("eu-west"));
+        let (Some(Cell::Shared(a)), Some(Cell::Shared(b))) = (table.get(0, 1), table.get(1, 1)) else {
+            panic!("region cells should be shared");
+        };
+        assert!(Arc::ptr_eq(a, b));
+        assert_eq!(table.get(0, 0), Some(&Cell::from("web-1")));
+        assert_eq!(table.get(2, 1), Some(&Cell::from("eu-west")));
+        assert_eq!(table.interned.len(), 2);
+    }
+
+    #[test]
+    fn test_cached_cell_widths() {
+        let mut table = Table::new()
+            .header("Name")
+            .row(Row::new().cell("\x1b[1mbold\x1b[0m"))
+            .row(Row::new().cell("plain"));
+        table.add_column("Note", vec!["a", "longer note"]).unwrap();
+        table.set(0, 0, "renamed");
+        table.sort_by_column("Note");
+        table.remove_column(0);
+        for (row, meta) in table.rows.iter().zip(table.meta.iter()) {
+            assert_eq!(meta.widths, RowMeta::measure(&table.headers, row, WidthStrategy::default()).widths);
+        }
+        assert_eq!(table.meta.iter().map(|m| m.widths.clone()).collect::<Vec<_>>(), vec![vec![1], vec![11]]);
+    }
+
+    #[test]
+    fn test_set_updates_widths_incrementally() {
+        let mut table = Table::new()
+            .header("Name")
+            .row(Row::new().cell("a"))
+            .row(Row::new().cell("widest"));
+        table.set(0, 0, "much wider");
+        assert_eq!(table.column_widths, vec![10]);
+        table.set(1, 0, "b");
+        assert_eq!(table.column_widths, vec![10]);
+        table.set(0, 0, "c");
+        assert_eq!(table.column_widths, vec![4]);
+        let mut headerless = Table::new().end_header().row(Row::new().cell("long cell"));
+        headerless.set(0, 0, "x");
+        assert_eq!(headerless.column_widths, vec![1]);
+    }
+
+    #[test]
+    fn test_shrink_to_fit() {
+        let mut table = Table::with_capacity(1000, 2).header("Id").header("Name").end_header();
+        table.extend((0..10).map(|i| Row::new().cell(i).cell(format!("row {i}"))));
+        let before = table.approx_memory_usage();
+        table.shrink_to_fit();
+        let after = table.approx_memory_usage();
+        assert!(after < before);
+        assert!(after > 10 * core::mem::size_of::<Cell>());
+        assert_eq!(table.rows.capacity(), 10);
+    }
+
+    #[test]
+    fn test_null_text_width() {
+        let mut table = Table::new()
+            .header("A")
+            .header("B")
+            .row(Row::new().cell("x").cell(None::<bool>))
+            .null_text("(missing)");
+        assert_eq!(table.empty_cells, vec![0, 1]);
+        assert_eq!(table.constrained_width(1, 1), 9);
+        table.set(0, 1, "y");
+        table.set(0, 0, Cell::Empty);
+        assert_eq!(table.empty_cells, vec![1, 0]);
+        assert_eq!((table.constrained_width(0, 1), table.constrained_width(1, 1)), (9, 1));
+        table.remove_row(0);
+        assert!(table.empty_cells.is_empty());
+    }
+
+    #[test]
+    fn test_approx_memory_usage() {
+        use core::mem::size_of;
+        let mut table = Table::new()
+            .header(Column::new("Host").intern())
+            .header("Id")
+            .row(Row::new().cell("web").cell(1))
+            .row(Row::new().cell("web").cell(2))
+            .row(Row::new().cell("db").cell(3));
+        table.shrink_to_fit();
+        let headers = 2 * size_of::<Header>() + "Host".len() + "Id".len();
+        let rows = 6 * size_of::<Cell>() + 3 * size_of::<usize>();
+        let meta = 3 * size_of::<RowMeta>() + 3 * 2 * size_of::<usize>();
+        let interned = 2 * size_of::<Arc<str>>() + "web".len() + "db".len();
+        assert_eq!(table.approx_memory_usage(),
+                   size_of::<Table<ModifyRows>>() + headers + 2 * 2 * size_of::<usize>() + rows + meta + interned);
+    }
+
+    #[test]
+    fn test_fixed_widths() {
+        let mut table = Table::new()
+            .header("Level")
+            .header("Message")
+            .fixed_widths(&[5, 10])
+            .end_header();
+        table.extend([
+            Row::new().cell("INFO").cell("started"),
+            Row::new().cell("ERROR").cell("disk is almost full"),
+        ]);
+        table.set(0, 1, "a much longer message");
+        assert_eq!(table.column_widths, vec![5, 7]);
+        assert_eq!(table.to_string(),
+                   "Level Message    \n".to_owned() +
+                       "INFO  a much lo… \n" +
+                       "ERROR disk is a… \n");
+    }
+
+    #[test]
+    fn test_extend_rows() {
+        let data = vec![
+            vec!["web-1".to_string(), "up".to_string()],
+            vec!["database-1".to_string(), "down".to_string()],
+        ];
+        let mut table = Table::new().header("Host").header("State").end_header();
+        table.extend_rows(data.clone());
+        let mut expected = Table::new().header("Host").header("State").end_header();
+        expected.extend(data.into_iter().map(|row| row.into_iter().fold(Row::new(), Row::cell)));
+        assert_eq!(table.to_string(), expected.to_string());
+        assert_eq!(table.column_widths, vec![10, 5]);
+    }
+
+    #[test]
+    fn test_invalid_text() {
+        let cell = Cell::from_utf8_lossy(b"caf\xe9");
+        assert_eq!(cell, Cell::from("caf\u{fffd}"));
+        assert_eq!(cell_width(&cell, WidthStrategy::default()), 4);
+        let table = Table::new()
+            .header("Name")
+            .invalid_text(InvalidTextPolicy::Substitute('?'))
+            .row(Row::new().cell(cell.clone()));
+        assert_eq!(table.get(0, 0), Some(&Cell::from("caf?")));
+        let table = Table::new()
+            .invalid_text(InvalidTextPolicy::Remove)
+            .end_header()
+            .row(Row::new().cell(cell));
+        assert_eq!(table.get(0, 0), Some(&Cell::from("caf")));
+    }
+
+    #[test]
+    fn test_try_render() {
+        let table = Table::new().header("Name").row(Row::new().cell("\x1b[1mok\x1b[0m"));
+        assert_eq!(table.try_render(), Ok(table.to_string()));
+        let table = Table::new().header("Name").header("Note").row(Row::new().cell("a").cell("two\nlines"));
+        assert_eq!(table.try_render(), Err(TableError::InvalidContent { row: 0, column: 1 }));
+        let table = Table::new().header("Blob").row(Row::new().cell("x".repeat(70_000)));
+        assert_eq!(table.try_render(), Ok(table.to_string()));
+        let table = table.fit_width(80, OverflowPolicy::Truncate);
+        assert_eq!(table.validate(), vec![TableError::WidthOverflow { column: 0, width: 70_000, max: 79 }]);
+        let mut table = Table::new().header("A").header("B").row(Row::new().cell(1).cell(2));
+        table.rows = vec![vec![Cell::from(1)]].into();
+        assert_eq!(table.try_render(), Err(TableError::Arity { row: 0, expected: 2, actual: 1 }));
+    }
+
+    #[test]
+    fn test_newline_policy() {
+        let row = || Row::new().cell("first\r\nsecond\nthird");
+        let table = Table::new().header("Note").newlines(NewlinePolicy::Escape).row(row());
+        assert_eq!(table.get(0, 0), Some(&Cell::from("first\\r\\nsecond\\nthird")));
+        let table = Table::new().header("Note").newlines(NewlinePolicy::Space).row(row());
+        assert_eq!(table.to_string(), "Note               \nfirst second third \n");
+        let table = Table::new().header("Note").row(row());
+        assert_eq!(table.get(0, 0), Some(&Cell::from("first\r\nsecond\nthird")));
+    }
+
+    #[test]
+    fn test_ansi_policy() {
+        let red = "\x1b[31mred\x1b[0m";
+        let table = Table::new().header("Color").row(Row::new().cell(red));
+        assert!(table.to_string().contains(red));
+        assert_eq!(table.render(OutputFormat::Csv), "Color\nred\n");
+        assert_eq!(table.render(OutputFormat::Json), "[{\"Color\":\"red\"}]\n");
+        let table = Table::new().header("Color").ansi_policy(AnsiPolicy::StripBeforeStore).row(Row::new().cell(red));
+        assert_eq!(table.get(0, 0), Some(&Cell::from("red")));
+        let table = Table::new().header("Color").ansi_policy(AnsiPolicy::Reject).row(Row::new().cell(red));
+        assert_eq!(table.try_render(), Err(TableError::InvalidContent { row: 0, column: 0 }));
+        assert_eq!(table.to_string(), "Color    \nred      \n");
+    }
+
+    #[test]
+    fn test_default_alignment() {
+        let table = Table::new()
+            .header("Name")
+            .header(Column::new("Note").align(Alignment::Left))
+            .default_alignment(Alignment::Right)
+            .row(Row::new().cell("a").cell("b"));
+        assert_eq!(table.to_string(), "Name     Note     \n       a b        \n");
+    }
+
+    #[test]
+    fn test_fit_width() {
+        let table = || Table::new()
+            .header(Column::new("Name").priority(2))
+            .header("Message")
+            .header(Column::new("Status").priority(1))
+            .row(Row::new().cell("web-1").cell("connection reset by peer").cell("down"));
+        assert_eq!(table().fit_width(40, OverflowPolicy::DropColumns).to_string(),
+                   "Name     Status   \nweb-1    down     \n");
+        assert_eq!(table().fit_width(40, OverflowPolicy::Wrap).to_string(),
+                   "Name     Message                  \nweb-1    connection reset by peer \n\nStatus   \ndown     \n");
+        let truncated = table().fit_width(40, OverflowPolicy::Truncate).to_string();
+        assert_eq!(truncated, "Name     Message                Status  \nweb-1    connection reset by p… down    \n");
+        assert!(truncated.lines().all(|line| width(line, WidthStrategy::default()) <= 40));
+        assert_eq!(table().fit_width(80, OverflowPolicy::Wrap).to_string(), table().to_string());
+    }
+
+    #[test]
+    fn test_fit_width_lines() {
+        let table = Table::new()
+            .header("Name")
+            .header("Message")
+            .header("Status")
+            .row(Row::new().cell("web-1").cell("connection reset by peer").cell("down"));
+        for policy in [OverflowPolicy::DropColumns, OverflowPolicy::Wrap, OverflowPolicy::Truncate] {
+            let table = table.clone().fit_width(40, policy);
+            let text: String = table.lines().map(|line| line + "\n").collect();
+            assert_eq!(text, table.to_string());
+            let borrowed = BorrowedTable::new(table.clone()).row(["web-1", "connection reset by peer", "down"]);
+            assert_eq!(borrowed.to_string(), table.to_string());
+        }
+    }
+
+    #[test]
+    fn test_shrink_weight() {
+        let table = Table::new()
+            .header(Column::new("Name").shrink_weight(0))
+            .header(Column::new("Message").shrink_weight(3))
+            .header("Status")
+            .row(Row::new().cell("web-1.example.com").cell("connection reset by peer").cell("down"))
+            .fit_width(40, OverflowPolicy::Truncate);
+        assert_eq!(table.to_string(), "Name              Message        Status \nweb-1.example.com connection re… down   \n");
+        let mut widths = vec![10, 10];
+        shrink_widths(&mut widths, &[0, 0], 10);
+        assert_eq!(widths, vec![4, 4]);
+    }
+
+    #[test]
+    fn test_validate() {
+        let mut table = Table::new().header("Id").header(" ").end_header();
+        table.try_push_row(Row::new().cell(1).cell("a\tb")).unwrap();
+        table.try_push_row(Row::new().cell(2).cell("ok")).unwrap();
+        table.rows.push(vec![Cell::from(3)]);
+        assert_eq!(table.validate(), vec![
+            TableError::EmptyHeader { column: 1 },
+            TableError::InvalidContent { row: 0, column: 1 },
+            TableError::Arity { row: 2, expected: 2, actual: 1 },
+        ]);
+        assert_eq!(table.try_render(), Err(TableError::InvalidContent { row: 0, column: 1 }));
+        assert!(Table::new().header("Id").row(Row::new().cell(1)).validate().is_empty());
+    }
+
+    #[test]
+    fn test_duplicate_headers() {
+        let table = Table::new().header("Id").header("Name").header("Id");
+        let error = DuplicateHeaderError { name: "Id".to_owned(), first: 0, duplicate: 2 };
+        assert_eq!(table.check_headers(), Err(error.clone()));
+        assert_eq!(Table::new().header("Id").header("Name").try_header("Id").unwrap_err(), error);
+        assert!(Table::new().try_header("Id").and_then(|t| t.try_header("Name")).unwrap().check_headers().is_ok());
+    }
+
+    #[test]
+    fn test_column_cells_follow_policies() {
+        let note = "\x1b[1mbold\x1b[0m\nnext";
+        for policy in [AnsiPolicy::KeepAndMeasureVisible, AnsiPolicy::StripBeforeStore, AnsiPolicy::Reject] {
+            let mut table = Table::new().ansi_policy(policy).newlines(NewlinePolicy::Escape).end_header();
+            table.add_column_data(Column::new("Note").intern(), vec![note]).unwrap();
+            table.add_column("Id", vec![1]).unwrap();
+            let stored = match policy {
+                AnsiPolicy::StripBeforeStore => "bold\\nnext",
+                _ => "\x1b[1mbold\x1b[0m\\nnext",
+            };
+            assert_eq!(table.get(0, 0), Some(&Cell::Shared(stored.into())));
+            assert_eq!(table.render(OutputFormat::Csv), "Note,Id\nbold\\nnext,1\n");
+            assert_eq!(table.try_render().is_err(), policy == AnsiPolicy::Reject);
+        }
+        let table: Table<ModifyRows> = Table::from_rows(vec![Column::new("Note").intern()], vec![vec![note], vec![note]]).unwrap();
+        assert!(matches!((table.get(0, 0), table.get(1, 0)), (Some(Cell::Shared(a)), Some(Cell::Shared(b))) if Arc::ptr_eq(a, b)));
+    }
+
+    #[test]
+    fn test_set_out_of_range_doesnt_intern() {
+        let mut table = Table::new().header(Column::new("Name").intern()).row(Row::new().cell("a"));
+        let before = table.clone();
+        assert_eq!(table.set(1, 0, "b"), None);
+        assert_eq!(table.set(0, 1, "b"), None);
+        assert_eq!(table.interned, before.interned);
+    }
+
+    #[test]
+    fn test_from_f32() {
+        assert_eq!(Cell::from(0.1f32).to_string(), "0.1");
+        assert_eq!(Cell::from(-3.3f32), Cell::Float(-3.3));
+        assert_eq!(Cell::from(f32::INFINITY), Cell::Float(f64::INFINITY));
+    }
+
+    #[test]
+    fn test_pad_wider_than_formatter_limit() {
+        let table = Table::new().header("Blob").header("Id")
+            .row(Row::new().cell("x".repeat(70_000)).cell(1))
+            .row(Row::new().cell("y").cell(2));
+        let text = table.to_string();
+        let lines: Vec<&str> = text.lines().collect();
+        assert_eq!(lines[2], "y".to_owned() + &" ".repeat(70_000) + "2        ");
+        assert_eq!(lines[0].len(), lines[2].len());
+    }
+
+    #[test]
+    fn test_width_strategies() {
+        assert_eq!(WidthStrategy::CharCount.str_width("日本é"), 3);
+        assert_eq!(WidthStrategy::ByteLength.str_width("日本é"), 8);
+        assert_eq!(WidthStrategy::ByteLength.char_width('é'), 2);
+        #[cfg(feature = "unicode-width")]
+        assert_eq!(WidthStrategy::UnicodeWidth.str_width("日本é"), 5);
+    }
+
+    #[test]
+    fn test_width_strategy() {
+        let table = Table::new()
+            .header("Name")
+            .header("Id")
+            .row(Row::new().cell("日本").cell(1))
+            .width_strategy(WidthStrategy::ByteLength);
+        assert_eq!(table.column_widths, vec![6, 2]);
+        assert_eq!(table.to_string(), "Name     Id       \n日本   1        \n");
+        let other = Table::new().header("N").row(Row::new().cell("日本"));
+        assert_eq!(other.column_widths, vec![if cfg!(feature = "unicode-width") { 4 } else { 2 }]);
+    }
+
+    #[test]
+    fn test_truncate_byte_length() {
+        assert_eq!(truncate("abcdef", 5, WidthStrategy::ByteLength), "ab…");
+        assert_eq!(truncate("abcdef", 2, WidthStrategy::ByteLength), "ab");
+        let table = Table::new()
+            .header(Column::new("Name").max_width(4))
+            .row(Row::new().cell("abcdefgh"))
+            .width_strategy(WidthStrategy::ByteLength);
+        assert_eq!(table.to_string(), "Name \na… \n");
+    }
+
+    #[test]
+    fn test_with_capacity() {
+        let table = Table::with_capacity(100, 2).header("A").header("B").end_header();
+        assert!(table.rows.capacity() >= 100);
+        let row: Row<0> = Row::with_capacity(2).cell(1).cell(2);
+        assert!(row.cells.capacity() >= 2);
+        assert_eq!(table.row(row).row_count(), 1);
+    }
+
+    #[test]
+    fn test_width() {
+        assert_eq!(width("plain", WidthStrategy::default()), 5);
+        #[cfg(feature = "unicode-width")]
+        assert_eq!(width("日本", WidthStrategy::default()), 4);
+        #[cfg(not(feature = "unicode-width"))]
+        assert_eq!(width("日本", WidthStrategy::default()), 2);
+        assert_eq!(width("\x1b[31mred\x1b[0m", WidthStrategy::default()), 3);
+    }
+
+    #[test]
+    #[cfg(feature = "std")]
+    fn test_write_to() {
+        let table = Table::new()
+            .header("Name")
+            .row(Row::new().cell("Alice"));
+        let mut out = Vec::new();
+        table.write_to(&mut out).unwrap();
+        assert_eq!(String::from_utf8(out).unwrap(), table.to_string());
+    }
+
+    #[test]
+    fn test_lines() {
+        let table = Table::new()
+            .header("Name")
+            .header("Age")
+            .row(Row::new().cell("Alice").cell(20))
+            .row(Row::new().cell("Bob").cell(30));
+        assert_eq!(table.lines().nth(2).as_deref(), Some("Bob      30       "));
+        let text: String = table.lines().map(|line| line + "\n").collect();
+        assert_eq!(text, table.to_string());
+        let empty = Table::new().header("Name").empty_message("(none)").end_header();
+        assert_eq!(empty.lines().collect::<Vec<_>>(), vec!["Name     ", "(none)"]);
+    }
+
+    #[test]
+    fn test_render_into_buffer() {
+        let mut table = Table::new().header("Tick").end_header();
+        let mut buf = String::from("stale");
+        table.render_into_buffer(&mut buf);
+        assert_eq!(buf, "Tick     \n");
+        table.try_push_row(Row::new().cell(1)).unwrap();
+        table.render_into_buffer(&mut buf);
+        let capacity = buf.capacity();
+        table.set(0, 0, 2);
+        table.render_into_buffer(&mut buf);
+        assert_eq!(buf, table.to_string());
+        assert_eq!(buf.capacity(), capacity);
+    }
+
+    #[test]
+    fn test_max_render_rows() {
+        let table = Table::new()
+            .header("Id")
+            .max_render_rows(2)
+            .end_header()
+            .row(Row::new().cell(1))
+            .row(Row::new().cell(2))
+            .row(Row::new().cell(3))
+            .row(Row::new().cell(4));
+        assert_eq!(table.omitted_rows(), 2);
+        assert_eq!(table.to_string(), "Id       \n1        \n2        \n… 2 more rows\n");
+        assert_eq!(table.lines().last().as_deref(), Some("… 2 more rows"));
+    }
+
+    #[cfg(feature = "compact_str")]
+    #[test]
+    fn test_compact_cells() {
+        let cell = Cell::from("running");
+        assert!(matches!(&cell, Cell::Compact(s) if !s.is_heap_allocated()));
+        assert_eq!(cell, Cell::Str("running".to_string()));
+        let table = Table::new().header("State").row(Row::new().cell("running"));
+        assert_eq!(table.to_string(), "State    \nrunning  \n");
+    }
+}
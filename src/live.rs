@@ -0,0 +1,99 @@
+use alloc::string::String;
+use alloc::vec::Vec;
+use core::fmt::Write;
+
+use crate::Table;
+
+/// Redraws a table in place on a terminal, rewriting only the lines that
+/// changed since the last frame. Meant for tables that are updated and shown
+/// again every tick, such as a process list.
+///
+/// The first [`render`](Self::render) prints the whole table. Each later call
+/// moves the cursor up over the previous frame with ANSI escapes, rewrites the
+/// lines that differ, and clears any left over if the table got shorter. The
+/// cursor ends up on the line after the table, as after printing it, so
+/// nothing else should be printed between frames.
+///
+/// ```
+/// use tabular2::{LiveRenderer, Row, Table};
+///
+/// let mut table = Table::new().header("Job").header("State").end_header();
+/// table.extend([Row::new().cell("build").cell("running"), Row::new().cell("test").cell("queued")]);
+/// let mut live = LiveRenderer::new();
+/// print!("{}", live.render(&table));
+/// table.set(1, 1, "running");
+/// // Up one line to the changed row, then rewrite it.
+/// assert_eq!(live.render(&table), "\x1b[1A\rtest     running  \x1b[K\n");
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct LiveRenderer {
+    /// The lines of the last frame.
+    lines: Vec<String>,
+}
+
+impl LiveRenderer {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// The text to write to bring the terminal from the last frame to
+    /// `table`. Empty if nothing changed.
+    pub fn render<T, const N: usize>(&mut self, table: &Table<T, N>) -> String {
+        let lines: Vec<String> = table.lines().collect();
+        let mut out = String::new();
+        // The cursor starts on the line after the last frame.
+        let mut cursor = self.lines.len();
+        for (i, line) in lines.iter().enumerate() {
+            if self.lines.get(i) == Some(line) {
+                continue;
+            }
+            move_cursor(&mut out, cursor, i);
+            write!(out, "\r{line}\x1b[K\n").expect("writing to a String can't fail");
+            cursor = i + 1;
+        }
+        if lines.len() < self.lines.len() {
+            move_cursor(&mut out, cursor, lines.len());
+            out.push_str("\x1b[J");
+            cursor = lines.len();
+        }
+        move_cursor(&mut out, cursor, lines.len());
+        self.lines = lines;
+        out
+    }
+
+    /// Forget the last frame, so the next [`render`](Self::render) prints the
+    /// whole table from wherever the cursor is.
+    pub fn reset(&mut self) {
+        self.lines.clear();
+    }
+}
+
+fn move_cursor(out: &mut String, from: usize, to: usize) {
+    let result = match from.cmp(&to) {
+        core::cmp::Ordering::Greater => write!(out, "\x1b[{}A", from - to),
+        core::cmp::Ordering::Less => write!(out, "\x1b[{}B", to - from),
+        core::cmp::Ordering::Equal => Ok(()),
+    };
+    result.expect("writing to a String can't fail");
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{LiveRenderer, Row, Table};
+
+    #[test]
+    fn test_live_renderer() {
+        let mut table = Table::new().header("Job").header("State").end_header();
+        table.extend([Row::new().cell("a").cell("ok"), Row::new().cell("b").cell("ok")]);
+        let mut live = LiveRenderer::new();
+        let first = live.render(&table);
+        assert_eq!(first, "\rJob      State    \x1b[K\n\ra        ok       \x1b[K\n\rb        ok       \x1b[K\n");
+        assert_eq!(live.render(&table), "");
+        table.set(0, 1, "failed");
+        assert_eq!(live.render(&table), "\x1b[2A\ra        failed   \x1b[K\n\x1b[1B");
+        table.remove_row(0);
+        assert_eq!(live.render(&table), "\x1b[2A\rb        ok       \x1b[K\n\x1b[J");
+        live.reset();
+        assert_eq!(live.render(&table), first.replace("a        ok       \x1b[K\n\r", ""));
+    }
+}
@@ -0,0 +1,99 @@
+use chrono::{NaiveDate, NaiveDateTime};
+use num_format::Buffer;
+
+use crate::Column;
+
+/// A locale's digit grouping, decimal mark and minus sign, for
+/// [`Column::locale_number`].
+pub use num_format::Locale as NumberLocale;
+/// A locale's month and day names, for [`Column::locale_date`].
+pub use chrono::Locale as DateLocale;
+
+impl Column {
+    /// Write numbers with the digit grouping, decimal mark and minus sign of
+    /// `locale`, so `1234567.5` becomes `1.234.567,5` in German. Cells that
+    /// aren't plain decimal numbers are left as they are.
+    ///
+    /// ```
+    /// use tabular2::{Column, NumberLocale, Row, Table};
+    ///
+    /// let table = Table::new()
+    ///     .header(Column::new("Betrag").locale_number(NumberLocale::de))
+    ///     .row(Row::new().cell(-1234567.5));
+    /// assert_eq!(table.to_string(), "Betrag       \n-1.234.567,5 \n");
+    /// ```
+    pub fn locale_number(self, locale: NumberLocale) -> Self {
+        self.format(move |value| format_number(value, locale).unwrap_or_else(|| value.to_string()))
+    }
+
+    /// Write dates (`2024-03-01`) and date-times (`2024-03-01 12:30:00`, with
+    /// a space or `T`) with the strftime `pattern`, using the month and day
+    /// names of `locale`. Other cells are left as they are.
+    ///
+    /// ```
+    /// use tabular2::{Column, DateLocale, Row, Table};
+    ///
+    /// let table = Table::new()
+    ///     .header(Column::new("Date").locale_date("%-d %B %Y", DateLocale::fr_FR))
+    ///     .row(Row::new().cell("2024-02-01"));
+    /// assert_eq!(table.to_string(), "Date           \n1 février 2024 \n");
+    /// ```
+    pub fn locale_date(self, pattern: &str, locale: DateLocale) -> Self {
+        let pattern = pattern.to_string();
+        self.format(move |value| format_date(value, &pattern, locale).unwrap_or_else(|| value.to_string()))
+    }
+}
+
+fn format_number(value: &str, locale: NumberLocale) -> Option<String> {
+    let value = value.trim();
+    let (negative, digits) = match value.strip_prefix('-') {
+        Some(rest) => (true, rest),
+        None => (false, value),
+    };
+    let (int, frac) = match digits.split_once('.') {
+        Some((int, frac)) => (int, Some(frac)),
+        None => (digits, None),
+    };
+    if frac.is_some_and(|frac| frac.is_empty() || !frac.bytes().all(|b| b.is_ascii_digit())) {
+        return None;
+    }
+    let int: u128 = int.parse().ok()?;
+    let mut buf = Buffer::new();
+    buf.write_formatted(&int, &locale);
+    let mut out = String::new();
+    if negative {
+        out.push_str(locale.minus_sign());
+    }
+    out.push_str(buf.as_str());
+    if let Some(frac) = frac {
+        out.push_str(locale.decimal());
+        out.push_str(frac);
+    }
+    Some(out)
+}
+
+fn format_date(value: &str, pattern: &str, locale: DateLocale) -> Option<String> {
+    let value = value.trim();
+    if let Ok(date) = NaiveDate::parse_from_str(value, "%Y-%m-%d") {
+        return Some(date.format_localized(pattern, locale).to_string());
+    }
+    ["%Y-%m-%d %H:%M:%S", "%Y-%m-%dT%H:%M:%S"].iter()
+        .find_map(|format| NaiveDateTime::parse_from_str(value, format).ok())
+        .map(|time| time.and_utc().format_localized(pattern, locale).to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{format_date, format_number};
+    use crate::{DateLocale, NumberLocale};
+
+    #[test]
+    fn test_locale_formats() {
+        assert_eq!(format_number("1234567", NumberLocale::en).as_deref(), Some("1,234,567"));
+        assert_eq!(format_number("-0.25", NumberLocale::fr).as_deref(), Some("-0,25"));
+        assert_eq!(format_number("12 apples", NumberLocale::en), None);
+        assert_eq!(format_number("1.", NumberLocale::en), None);
+        assert_eq!(format_date("2024-03-01T08:00:00", "%A %-d %B", DateLocale::de_DE).as_deref(), Some("Freitag 1 März"));
+        assert_eq!(format_date("soon", "%B", DateLocale::de_DE), None);
+    }
+}
@@ -0,0 +1,111 @@
+use log::kv::{self, Key, Source, ToValue, Value, VisitSource};
+use log::{Level, Metadata, Record};
+
+use crate::{Cell, Table};
+
+impl<T, const N: usize> Table<T, N> {
+    /// Emit each row as a `log` record with one key-value pair per cell, keyed by
+    /// header text, or by column index for tables without headers. Numbers and
+    /// booleans keep their type, so log pipelines can query them. Nothing is
+    /// built if the logger filters out `level`.
+    pub fn log_rows(&self, level: Level) {
+        if level > log::max_level() {
+            return;
+        }
+        let logger = log::logger();
+        if !logger.enabled(&Metadata::builder().level(level).target("tabular2").build()) {
+            return;
+        }
+        let keys: Vec<String> = (0..self.column_widths.len())
+            .map(|i| self.headers.get(i).map(|h| h.text.clone()).unwrap_or_else(|| i.to_string()))
+            .collect();
+        for (index, cells) in self.rows.iter().enumerate() {
+            let fields = RowFields { keys: &keys, cells };
+            logger.log(&Record::builder()
+                .level(level)
+                .target("tabular2")
+                .args(format_args!("row {index}"))
+                .key_values(&fields)
+                .build());
+        }
+    }
+}
+
+struct RowFields<'a> {
+    keys: &'a [String],
+    cells: &'a [Cell],
+}
+
+impl Source for RowFields<'_> {
+    fn visit<'kvs>(&'kvs self, visitor: &mut dyn VisitSource<'kvs>) -> Result<(), kv::Error> {
+        for (key, cell) in self.keys.iter().zip(self.cells.iter()) {
+            let value = match cell {
+                Cell::Str(s) => s.as_str().to_value(),
+                Cell::Shared(s) => s.as_ref().to_value(),
+                #[cfg(feature = "compact_str")]
+                Cell::Compact(s) => s.as_str().to_value(),
+                Cell::Int(n) => n.to_value(),
+                Cell::Float(n) => n.to_value(),
+                Cell::Bool(b) => b.to_value(),
+                Cell::Empty => Value::null(),
+            };
+            visitor.visit_pair(Key::from_str(key), value)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Mutex;
+
+    use log::kv::{self, Key, Value, VisitSource};
+    use log::{Level, Log, Metadata, Record};
+
+    use crate::{ModifyRows, Row, Table};
+
+    static RECORDS: Mutex<Vec<String>> = Mutex::new(Vec::new());
+
+    struct Capture;
+
+    impl Log for Capture {
+        /// Warnings are filtered here rather than by the max level, to see
+        /// that `log_rows` asks.
+        fn enabled(&self, metadata: &Metadata) -> bool {
+            metadata.level() != Level::Warn
+        }
+
+        fn log(&self, record: &Record) {
+            struct Pairs(String);
+            impl<'kvs> VisitSource<'kvs> for Pairs {
+                fn visit_pair(&mut self, key: Key<'kvs>, value: Value<'kvs>) -> Result<(), kv::Error> {
+                    self.0 += &format!(" {key}={value:?}");
+                    Ok(())
+                }
+            }
+            let mut pairs = Pairs(record.args().to_string());
+            record.key_values().visit(&mut pairs).unwrap();
+            RECORDS.lock().unwrap().push(pairs.0);
+        }
+
+        fn flush(&self) {}
+    }
+
+    #[test]
+    fn test_log_rows() {
+        log::set_logger(&Capture).unwrap();
+        log::set_max_level(log::LevelFilter::Info);
+        let table: Table<ModifyRows> = Table::new()
+            .header("Host")
+            .header("Up")
+            .row(Row::new().cell("web-1").cell(true))
+            .row(Row::new().cell("db-1").cell(None::<bool>));
+        table.log_rows(Level::Info);
+        table.log_rows(Level::Debug);
+        table.log_rows(Level::Warn);
+        assert_eq!(*RECORDS.lock().unwrap(), vec![
+            "row 0 Host=\"web-1\" Up=true",
+            "row 1 Host=\"db-1\" Up=None",
+        ]);
+    }
+}
@@ -0,0 +1,401 @@
+use alloc::borrow::Cow;
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+use core::fmt::Write;
+use core::str::FromStr;
+
+use crate::{ansi, write_padded, Alignment, Cell, Table};
+
+/// A format a table can be rendered in, typically chosen with a CLI `--output`
+/// flag. Parses from and displays as `plain`, `markdown`, `csv` or `json`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub enum OutputFormat {
+    /// Aligned columns, the same as `Display`.
+    #[default]
+    Plain,
+    /// A GitHub-flavored Markdown table. Pipes, backticks, `<`, `&` and line
+    /// breaks in cells are escaped, so the text can't break the table or be
+    /// read as markup.
+    Markdown,
+    /// Comma-separated values, with the headers as the first record. See
+    /// [`CsvOptions`] for other delimiters, quoting and line endings.
+    Csv,
+    /// An array of objects keyed by header, or of arrays if there are no headers.
+    Json,
+}
+
+impl OutputFormat {
+    /// Every format, in the order they are listed in help text.
+    pub const ALL: [OutputFormat; 4] = [OutputFormat::Plain, OutputFormat::Markdown, OutputFormat::Csv, OutputFormat::Json];
+
+    fn name(&self) -> &'static str {
+        match self {
+            OutputFormat::Plain => "plain",
+            OutputFormat::Markdown => "markdown",
+            OutputFormat::Csv => "csv",
+            OutputFormat::Json => "json",
+        }
+    }
+}
+
+impl core::fmt::Display for OutputFormat {
+    fn fmt(&self, f: &mut core::fmt::Formatter) -> core::fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+/// Returned when parsing an unknown [`OutputFormat`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseOutputFormatError(pub String);
+
+impl core::fmt::Display for ParseOutputFormatError {
+    fn fmt(&self, f: &mut core::fmt::Formatter) -> core::fmt::Result {
+        write!(f, "unknown output format `{}`, expected plain, markdown, csv or json", self.0)
+    }
+}
+
+impl core::error::Error for ParseOutputFormatError {}
+
+impl FromStr for OutputFormat {
+    type Err = ParseOutputFormatError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_str() {
+            "plain" | "table" => Ok(OutputFormat::Plain),
+            "markdown" | "md" => Ok(OutputFormat::Markdown),
+            "csv" => Ok(OutputFormat::Csv),
+            "json" => Ok(OutputFormat::Json),
+            _ => Err(ParseOutputFormatError(s.to_string())),
+        }
+    }
+}
+
+#[cfg(feature = "clap")]
+impl clap::ValueEnum for OutputFormat {
+    fn value_variants<'a>() -> &'a [Self] {
+        &OutputFormat::ALL
+    }
+
+    fn to_possible_value(&self) -> Option<clap::builder::PossibleValue> {
+        Some(clap::builder::PossibleValue::new(self.name()))
+    }
+}
+
+/// When [`OutputFormat::Csv`] puts a field in quotes.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum CsvQuoting {
+    /// Only fields holding the delimiter, a quote or a line break.
+    #[default]
+    Minimal,
+    /// Every field, including empty ones.
+    Always,
+}
+
+/// What ends each line of text output.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum LineEnding {
+    #[default]
+    Lf,
+    /// `\r\n`, as Excel and RFC 4180 expect.
+    CrLf,
+}
+
+impl LineEnding {
+    fn as_str(&self) -> &'static str {
+        match self {
+            LineEnding::Lf => "\n",
+            LineEnding::CrLf => "\r\n",
+        }
+    }
+}
+
+/// How [`OutputFormat::Csv`] writes records, set with
+/// [`Table::csv_options`]. The default is comma-separated, quoted only where
+/// needed, with `\n` line endings.
+///
+/// ```
+/// use tabular2::{CsvOptions, CsvQuoting, LineEnding, OutputFormat, Row, Table};
+///
+/// let options = CsvOptions::new().delimiter(';').quoting(CsvQuoting::Always).line_ending(LineEnding::CrLf);
+/// let table = Table::new().header("Id").header("Name").row(Row::new().cell(1).cell("Ada")).csv_options(options);
+/// assert_eq!(table.render(OutputFormat::Csv), "\"Id\";\"Name\"\r\n\"1\";\"Ada\"\r\n");
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CsvOptions {
+    pub delimiter: char,
+    pub quoting: CsvQuoting,
+    pub line_ending: LineEnding,
+}
+
+impl Default for CsvOptions {
+    fn default() -> Self {
+        CsvOptions {
+            delimiter: ',',
+            quoting: CsvQuoting::Minimal,
+            line_ending: LineEnding::Lf,
+        }
+    }
+}
+
+impl CsvOptions {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Separate fields with `delimiter`, such as `;` or `\t`.
+    pub fn delimiter(mut self, delimiter: char) -> Self {
+        self.delimiter = delimiter;
+        self
+    }
+
+    pub fn quoting(mut self, quoting: CsvQuoting) -> Self {
+        self.quoting = quoting;
+        self
+    }
+
+    pub fn line_ending(mut self, line_ending: LineEnding) -> Self {
+        self.line_ending = line_ending;
+        self
+    }
+}
+
+impl<T, const N: usize> Table<T, N> {
+    /// Render the table in `format`.
+    pub fn render(&self, format: OutputFormat) -> String {
+        let mut out = String::new();
+        self.render_to(format, &mut out).expect("writing to a String can't fail");
+        out
+    }
+
+    /// Render the table in `format` into any `fmt::Write`, such as a buffer
+    /// owned by the caller. Needs neither `std` nor a terminal.
+    pub fn render_to<W: Write>(&self, format: OutputFormat, out: &mut W) -> core::fmt::Result {
+        match format {
+            OutputFormat::Plain => self.write_plain(out),
+            OutputFormat::Markdown => self.write_markdown(out),
+            OutputFormat::Csv => self.write_csv(out),
+            OutputFormat::Json => self.write_json(out),
+        }
+    }
+
+    fn write_markdown<W: Write>(&self, f: &mut W) -> core::fmt::Result {
+        // Unless widths are fixed, the tracked widths cover the text before
+        // escaping, which only lengthens it, so only escaped text needs
+        // measuring again. Fixed widths aren't tracked, and an export mustn't
+        // truncate to them, so then every cell is measured.
+        let measure_all = self.config.fixed_widths.is_some();
+        let columns = match measure_all {
+            true => self.rows.iter().map(<[Cell]>::len).fold(self.column_widths.len(), core::cmp::max),
+            false => self.column_widths.len(),
+        };
+        let mut widths: Vec<usize> = (0..columns)
+            .map(|i| core::cmp::max(self.column_widths.get(i).copied().unwrap_or(0), 3))
+            .collect();
+        let texts = self.headers.iter()
+            .map(|header| Cow::Borrowed(header.text.as_str()))
+            .enumerate()
+            .chain(self.rows.iter().flat_map(|row| row.iter().enumerate().map(|(i, cell)| (i, self.shown_text(i, cell)))));
+        for (i, text) in texts {
+            let escaped = markdown_escape(&text);
+            if let (true, Some(width)) = (measure_all || matches!(escaped, Cow::Owned(_)), widths.get_mut(i)) {
+                *width = core::cmp::max(*width, crate::width(&escaped, self.config.width_strategy));
+            }
+        }
+        write!(f, "|")?;
+        for (i, width) in widths.iter().enumerate() {
+            let (text, alignment) = match self.headers.get(i) {
+                Some(header) => (markdown_escape(&header.text), header.alignment),
+                None => (Cow::Borrowed(""), Alignment::Left),
+            };
+            f.write_char(' ')?;
+            write_padded(f, &text, crate::width(&text, self.config.width_strategy), *width, alignment, self.config.width_strategy)?;
+            f.write_str(" |")?;
+        }
+        writeln!(f)?;
+        write!(f, "|")?;
+        for (i, width) in widths.iter().enumerate() {
+            let rule = match self.cell_alignment(i) {
+                Alignment::Left => "-".repeat(*width),
+                Alignment::Right => "-".repeat(width - 1) + ":",
+                Alignment::Center => ":".to_string() + &"-".repeat(width - 2) + ":",
+            };
+            write!(f, " {rule} |")?;
+        }
+        writeln!(f)?;
+        for row in self.rows.iter() {
+            write!(f, "|")?;
+            for (i, width) in widths.iter().enumerate() {
+                let text = row.get(i).map(|cell| self.shown_text(i, cell)).unwrap_or_default();
+                let text = markdown_escape(&text);
+                f.write_char(' ')?;
+                write_padded(f, &text, crate::width(&text, self.config.width_strategy), *width, self.cell_alignment(i), self.config.width_strategy)?;
+                f.write_str(" |")?;
+            }
+            writeln!(f)?;
+        }
+        Ok(())
+    }
+
+    fn write_csv<W: Write>(&self, f: &mut W) -> core::fmt::Result {
+        let CsvOptions { delimiter, quoting, line_ending } = self.config.csv;
+        let record = |f: &mut W, fields: &mut dyn Iterator<Item = &str>| -> core::fmt::Result {
+            for (i, field) in fields.enumerate() {
+                if i > 0 {
+                    f.write_char(delimiter)?;
+                }
+                if quoting == CsvQuoting::Always || field.contains([delimiter, '"', '\n', '\r']) {
+                    write!(f, "\"{}\"", field.replace('"', "\"\""))?;
+                } else {
+                    f.write_str(field)?;
+                }
+            }
+            f.write_str(line_ending.as_str())
+        };
+        if !self.headers.is_empty() {
+            let texts: Vec<_> = self.headers.iter().map(|h| ansi::strip_str(&h.text)).collect();
+            record(f, &mut texts.iter().map(|t| t.as_ref()))?;
+        }
+        for row in self.rows.iter() {
+            let texts: Vec<String> = row.iter()
+                .enumerate()
+                .map(|(i, cell)| ansi::strip_str(&self.shown_text(i, cell)).into_owned())
+                .collect();
+            record(f, &mut texts.iter().map(String::as_str))?;
+        }
+        Ok(())
+    }
+
+    fn write_json<W: Write>(&self, f: &mut W) -> core::fmt::Result {
+        f.write_char('[')?;
+        for (r, row) in self.rows.iter().enumerate() {
+            if r > 0 {
+                f.write_char(',')?;
+            }
+            self.write_json_row(f, row)?;
+        }
+        writeln!(f, "]")
+    }
+
+    /// Write `row` as a JSON object keyed by header, or as an array if there
+    /// are no headers.
+    pub(crate) fn write_json_row<W: Write>(&self, f: &mut W, row: &[Cell]) -> core::fmt::Result {
+        if self.headers.is_empty() {
+            f.write_char('[')?;
+            for (i, cell) in row.iter().enumerate() {
+                if i > 0 {
+                    f.write_char(',')?;
+                }
+                json_value(f, cell)?;
+            }
+            return f.write_char(']');
+        }
+        f.write_char('{')?;
+        for (i, header) in self.headers.iter().enumerate() {
+            if i > 0 {
+                f.write_char(',')?;
+            }
+            json_string(f, &header.text)?;
+            f.write_char(':')?;
+            json_value(f, crate::cell(row, i))?;
+        }
+        f.write_char('}')
+    }
+}
+
+/// Escape the characters of a Markdown cell that would end the cell or the
+/// row, or be read as markup.
+fn markdown_escape(s: &str) -> Cow<'_, str> {
+    if !s.contains(['|', '<', '&', '`', '\n', '\r']) {
+        return Cow::Borrowed(s);
+    }
+    let mut out = String::with_capacity(s.len() + 8);
+    for c in s.chars() {
+        match c {
+            '|' => out.push_str("\\|"),
+            '`' => out.push_str("\\`"),
+            '<' => out.push_str("&lt;"),
+            '&' => out.push_str("&amp;"),
+            '\n' => out.push_str("<br>"),
+            '\r' => {}
+            c => out.push(c),
+        }
+    }
+    Cow::Owned(out)
+}
+
+fn json_value<W: Write>(f: &mut W, cell: &Cell) -> core::fmt::Result {
+    match cell {
+        Cell::Str(s) => json_string(f, s),
+        Cell::Shared(s) => json_string(f, s),
+        #[cfg(feature = "compact_str")]
+        Cell::Compact(s) => json_string(f, s),
+        Cell::Int(n) => write!(f, "{n}"),
+        Cell::Float(n) if n.is_finite() => write!(f, "{n}"),
+        Cell::Bool(b) => write!(f, "{b}"),
+        Cell::Float(_) | Cell::Empty => f.write_str("null"),
+    }
+}
+
+fn json_string<W: Write>(f: &mut W, s: &str) -> core::fmt::Result {
+    f.write_char('"')?;
+    for c in ansi::strip_str(s).chars() {
+        match c {
+            '"' => f.write_str("\\\"")?,
+            '\\' => f.write_str("\\\\")?,
+            '\n' => f.write_str("\\n")?,
+            '\r' => f.write_str("\\r")?,
+            '\t' => f.write_str("\\t")?,
+            c if (c as u32) < 0x20 => write!(f, "\\u{:04x}", c as u32)?,
+            c => f.write_char(c)?,
+        }
+    }
+    f.write_char('"')
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{Alignment, Column, CsvOptions, CsvQuoting, LineEnding, ModifyRows, OutputFormat, Row, Table};
+
+    #[test]
+    fn test_render_formats() {
+        let table: Table<ModifyRows> = Table::new()
+            .header("Name")
+            .header(Column::new("Count").align(Alignment::Right))
+            .row(Row::new().cell("a, \"b\"").cell(3))
+            .row(Row::new().cell("c").cell(None::<i64>));
+        assert_eq!(table.render(OutputFormat::Plain), table.to_string());
+        assert_eq!(table.render(OutputFormat::Markdown),
+                   "| Name   | Count |\n".to_owned() +
+                       "| ------ | ----: |\n" +
+                       "| a, \"b\" |     3 |\n" +
+                       "| c      |       |\n");
+        assert_eq!(table.render(OutputFormat::Csv), "Name,Count\n\"a, \"\"b\"\"\",3\nc,\n");
+        let mut out = String::from("csv: ");
+        table.render_to(OutputFormat::Csv, &mut out).unwrap();
+        assert_eq!(out, "csv: Name,Count\n\"a, \"\"b\"\"\",3\nc,\n");
+        assert_eq!(table.render(OutputFormat::Json),
+                   "[{\"Name\":\"a, \\\"b\\\"\",\"Count\":3},{\"Name\":\"c\",\"Count\":null}]\n");
+
+        let tabs = table.clone().csv_options(CsvOptions::new().delimiter('\t'));
+        assert_eq!(tabs.render(OutputFormat::Csv), "Name\tCount\n\"a, \"\"b\"\"\"\t3\nc\t\n");
+        let quoted = table.csv_options(CsvOptions::new().quoting(CsvQuoting::Always).line_ending(LineEnding::CrLf));
+        assert_eq!(quoted.render(OutputFormat::Csv), "\"Name\",\"Count\"\r\n\"a, \"\"b\"\"\",\"3\"\r\n\"c\",\"\"\r\n");
+
+        let fixed = Table::new().header("Id").row(Row::new().cell("abcdefgh")).fixed_widths(&[3]);
+        assert_eq!(fixed.render(OutputFormat::Markdown), "| Id       |\n| -------- |\n| abcdefgh |\n");
+
+        let table = Table::new()
+            .header("Code")
+            .row(Row::new().cell("a | b"))
+            .row(Row::new().cell("`<b>` & co\nnext"));
+        assert_eq!(table.render(OutputFormat::Markdown),
+                   "| Code                        |\n".to_owned() +
+                       "| --------------------------- |\n" +
+                       "| a \\| b                      |\n" +
+                       "| \\`&lt;b>\\` &amp; co<br>next |\n");
+
+        assert_eq!("md".parse(), Ok(OutputFormat::Markdown));
+        assert_eq!(OutputFormat::Json.to_string(), "json");
+        assert!("yaml".parse::<OutputFormat>().is_err());
+    }
+}
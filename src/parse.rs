@@ -0,0 +1,271 @@
+use alloc::string::{String, ToString};
+use alloc::vec;
+use alloc::vec::Vec;
+
+use crate::{ansi, Alignment, Cell, Config, Header, ModifyRows, Table, WidthStrategy};
+
+impl<const N: usize> Table<ModifyRows, N> {
+    /// Read back a table rendered by `Display`, taking the first line as the
+    /// headers. Every cell is stored as text, and blank cells as empty.
+    ///
+    /// Columns are found by position: a column boundary is a position that is
+    /// blank on every line and followed by header text, so a header with
+    /// spaces that line up with blanks on every line is split. Columns whose
+    /// cells all end at the same position are right-aligned.
+    ///
+    /// ```
+    /// use tabular2::{ModifyRows, Row, Table};
+    ///
+    /// let table = Table::new()
+    ///     .header("Host")
+    ///     .header("State")
+    ///     .row(Row::new().cell("web-1").cell("up"))
+    ///     .row(Row::new().cell("db-1").cell("down"));
+    /// let parsed: Table<ModifyRows> = Table::parse_plain(&table.to_string());
+    /// assert_eq!(parsed.to_string(), table.to_string());
+    /// ```
+    pub fn parse_plain(text: &str) -> Self {
+        let lines: Vec<Vec<(usize, char)>> = text.lines()
+            .filter(|line| !line.trim().is_empty())
+            .map(|line| positioned(&ansi::strip_str(line)))
+            .collect();
+        let spans = spans(&lines);
+        let mut columns: Vec<Vec<Option<Text>>> = lines.iter()
+            .map(|line| spans.iter().map(|&(start, end)| text_in(line, start, end)).collect())
+            .collect();
+        if columns.is_empty() {
+            return Table::from_parts(Vec::new(), Vec::new(), Config::default());
+        }
+        let header_texts = columns.remove(0);
+        let headers = header_texts.into_iter()
+            .zip(spans.iter())
+            .enumerate()
+            .map(|(i, (text, &(start, end)))| {
+                let mut header: Header = text.as_ref().map_or("", |t| t.text.as_str()).into();
+                let cells = || columns.iter().filter_map(|row| row[i].as_ref());
+                let right = cells().any(|t| t.start > start) && cells().all(|t| t.end == end);
+                if right {
+                    header.cell_alignment = Some(Alignment::Right);
+                }
+                if text.is_some_and(|t| t.start > start || (right && t.end == end)) {
+                    header.alignment = Alignment::Right;
+                }
+                header
+            })
+            .collect();
+        let rows = columns.into_iter()
+            .map(|row| row.into_iter().map(|text| text.map_or(Cell::Empty, |t| t.text.into())).collect())
+            .collect();
+        Table::from_parts(headers, rows, Config::default())
+    }
+
+    /// Read the first pipe table in `text`, as written in Markdown: a header
+    /// row, a delimiter row whose colons set the alignment of each column,
+    /// and the rows up to the first line without a `|`. Every cell is stored
+    /// as text, and blank cells as empty. Rows are padded or cut to the
+    /// number of headers.
+    ///
+    /// ```
+    /// use tabular2::{ModifyRows, Table};
+    ///
+    /// let text = "| Item | Price |\n| :--- | ----: |\n| Tea | 2.50 |\n";
+    /// let table: Table<ModifyRows> = Table::parse_markdown(text).unwrap();
+    /// assert_eq!(table.to_string(), "Item        Price \nTea          2.50 \n");
+    /// ```
+    pub fn parse_markdown(text: &str) -> Result<Self, ParseMarkdownError> {
+        let lines: Vec<&str> = text.lines().collect();
+        let start = lines.windows(2)
+            .position(|pair| pair[0].contains('|') && delimiter_row(pair[1]).is_some_and(|a| a.len() == split_row(pair[0]).len()))
+            .ok_or(ParseMarkdownError)?;
+        let alignments = delimiter_row(lines[start + 1]).ok_or(ParseMarkdownError)?;
+        let headers: Vec<Header> = split_row(lines[start]).iter()
+            .zip(alignments)
+            .map(|(text, alignment)| {
+                let mut header: Header = text.as_str().into();
+                header.alignment = alignment;
+                header.cell_alignment = Some(alignment);
+                header
+            })
+            .collect();
+        let rows = lines[start + 2..].iter()
+            .take_while(|line| line.contains('|'))
+            .map(|line| {
+                let mut cells: Vec<Cell> = split_row(line).into_iter()
+                    .map(|text| if text.is_empty() { Cell::Empty } else { text.into() })
+                    .collect();
+                cells.resize(headers.len(), Cell::Empty);
+                cells
+            })
+            .collect();
+        Ok(Table::from_parts(headers, rows, Config::default()))
+    }
+}
+
+/// Returned by [`Table::parse_markdown`] when the text has no pipe table.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ParseMarkdownError;
+
+impl core::fmt::Display for ParseMarkdownError {
+    fn fmt(&self, f: &mut core::fmt::Formatter) -> core::fmt::Result {
+        f.write_str("no Markdown table found: expected a header row followed by a row like `| --- | --- |`")
+    }
+}
+
+impl core::error::Error for ParseMarkdownError {}
+
+/// The trimmed cells of a Markdown table row. `\|` is a literal pipe.
+fn split_row(line: &str) -> Vec<String> {
+    let line = line.trim();
+    let line = line.strip_prefix('|').unwrap_or(line);
+    let mut cells = vec![String::new()];
+    let mut chars = line.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '\\' if matches!(chars.peek(), Some('|' | '`')) => cells.last_mut().unwrap().push(chars.next().unwrap()),
+            '|' => cells.push(String::new()),
+            c => cells.last_mut().unwrap().push(c),
+        }
+    }
+    // A closing pipe leaves an empty last cell.
+    if cells.len() > 1 && cells.last().is_some_and(|cell| cell.trim().is_empty()) && line.trim_end().ends_with('|') {
+        cells.pop();
+    }
+    cells.into_iter().map(|cell| unescape(cell.trim())).collect()
+}
+
+/// Undo the entities and line breaks `render` writes into Markdown cells.
+fn unescape(cell: &str) -> String {
+    if !cell.contains(['&', '<']) {
+        return cell.to_string();
+    }
+    cell.replace("<br>", "\n").replace("&lt;", "<").replace("&gt;", ">").replace("&amp;", "&")
+}
+
+/// The column alignments set by a delimiter row like `| :-- | --: |`, or
+/// `None` if `line` isn't one.
+fn delimiter_row(line: &str) -> Option<Vec<Alignment>> {
+    if !line.contains('-') {
+        return None;
+    }
+    split_row(line).iter()
+        .map(|cell| {
+            let dashes = cell.trim_start_matches(':').trim_end_matches(':');
+            if dashes.is_empty() || !dashes.bytes().all(|b| b == b'-') {
+                return None;
+            }
+            Some(match (cell.starts_with(':'), cell.ends_with(':')) {
+                (true, true) => Alignment::Center,
+                (false, true) => Alignment::Right,
+                _ => Alignment::Left,
+            })
+        })
+        .collect()
+}
+
+/// The trimmed text of a cell and the positions it starts and ends at.
+struct Text {
+    text: String,
+    start: usize,
+    end: usize,
+}
+
+/// Each character of `line` with the position, in columns, it is shown at.
+fn positioned(line: &str) -> Vec<(usize, char)> {
+    let mut pos = 0;
+    line.chars()
+        .map(|c| {
+            let at = pos;
+            pos += WidthStrategy::default().char_width(c);
+            (at, c)
+        })
+        .collect()
+}
+
+/// The `start..end` ranges of positions that have text on some line. A range
+/// without header text is part of the column before it, as where a cell has
+/// spaces that line up with blanks on the other lines.
+fn spans(lines: &[Vec<(usize, char)>]) -> Vec<(usize, usize)> {
+    let mut used = Vec::new();
+    for &(pos, c) in lines.iter().flatten() {
+        if c.is_whitespace() {
+            continue;
+        }
+        let end = pos + WidthStrategy::default().char_width(c).max(1);
+        if used.len() < end {
+            used.resize(end, false);
+        }
+        used[pos..end].fill(true);
+    }
+    let header = lines.first().map_or(&[][..], Vec::as_slice);
+    let mut spans: Vec<(usize, usize)> = vec![];
+    let mut start = None;
+    for (pos, used) in used.iter().chain([&false]).enumerate() {
+        match (start, used) {
+            (None, true) => start = Some(pos),
+            (Some(s), false) => {
+                let headed = header.iter().any(|&(p, c)| (s..pos).contains(&p) && !c.is_whitespace());
+                match spans.last_mut() {
+                    Some(last) if !headed => last.1 = pos,
+                    _ => spans.push((s, pos)),
+                }
+                start = None;
+            }
+            _ => {}
+        }
+    }
+    spans
+}
+
+fn text_in(line: &[(usize, char)], start: usize, end: usize) -> Option<Text> {
+    let chars: Vec<&(usize, char)> = line.iter()
+        .filter(|(pos, _)| (start..end).contains(pos))
+        .collect();
+    let first = chars.iter().position(|(_, c)| !c.is_whitespace())?;
+    let last = chars.iter().rposition(|(_, c)| !c.is_whitespace())?;
+    let (last_pos, last_char) = *chars[last];
+    Some(Text {
+        text: chars[first..=last].iter().map(|(_, c)| c).collect(),
+        start: chars[first].0,
+        end: last_pos + WidthStrategy::default().char_width(last_char).max(1),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{Alignment, Cell, Column, ModifyRows, OutputFormat, ParseMarkdownError, Row, Table};
+
+    #[test]
+    fn test_parse_plain() {
+        let table = Table::new()
+            .header("Name")
+            .header(Column::new("Count").align(Alignment::Right))
+            .header("Note")
+            .row(Row::new().cell("日本").cell(3).cell("a long note"))
+            .row(Row::new().cell("beta").cell(12345).cell(None::<&str>))
+            .row(Row::new().cell("gamma delta").cell(7).cell("short"));
+        let text = table.to_string();
+        let parsed: Table<ModifyRows> = Table::parse_plain(&text);
+        assert_eq!(parsed.to_string(), text);
+        assert_eq!(parsed.get(1, 2), Some(&Cell::Empty));
+        assert_eq!(parsed.get(2, 0), Some(&Cell::from("gamma delta")));
+        assert_eq!(parsed.headers()[1].cell_alignment, Some(Alignment::Right));
+        assert!(Table::<ModifyRows>::parse_plain("").headers().is_empty());
+    }
+
+    #[test]
+    fn test_parse_markdown() {
+        let text = "Prices:\n\n| Item | Price | Note |\n|:-:|--:|---|\n| Tea | 2.50 | a \\| b |\n| Cake | |\n\nAfter.\n";
+        let mut table: Table<ModifyRows> = Table::parse_markdown(text).unwrap();
+        assert_eq!(table.headers()[0].alignment, Alignment::Center);
+        assert_eq!(table.headers()[1].cell_alignment, Some(Alignment::Right));
+        assert_eq!(table.get(0, 2), Some(&Cell::from("a | b")));
+        assert_eq!(table.rows().nth(1), Some(&[Cell::from("Cake"), Cell::Empty, Cell::Empty][..]));
+        assert_eq!(table.row_count(), 2);
+        table.set(1, 2, "<b> & `c`");
+        let rendered = table.render(OutputFormat::Markdown);
+        let reparsed: Table<ModifyRows> = Table::parse_markdown(&rendered).unwrap();
+        assert_eq!(reparsed.render(OutputFormat::Markdown), rendered);
+        assert_eq!(reparsed.get(1, 2), Some(&Cell::from("<b> & `c`")));
+        assert_eq!(Table::<ModifyRows>::parse_markdown("| a |\n| b |\n"), Err(ParseMarkdownError));
+    }
+}
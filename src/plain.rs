@@ -0,0 +1,145 @@
+use alloc::borrow::Cow;
+use alloc::string::String;
+use alloc::vec;
+use alloc::vec::Vec;
+use core::fmt::Write;
+
+use crate::{write_padded, Table};
+
+/// What [`PlainLines`] writes next.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Step {
+    /// The blank line between two groups of columns.
+    Gap,
+    Header,
+    Rows,
+    Footer,
+    Done,
+}
+
+/// The lines of the plain format, produced one at a time. `Display`,
+/// [`Table::lines`] and the other renderers of whole tables all go through
+/// this, so that they lay a table out alike, down to the column groups of
+/// [`fit_width`](Table::fit_width).
+pub(crate) struct PlainLines<'a, T, const N: usize, R> {
+    table: &'a Table<T, N>,
+    widths: Vec<usize>,
+    /// Which columns each group of lines shows. There is one group unless the
+    /// table is wrapped to fit its width.
+    groups: Vec<Vec<bool>>,
+    group: usize,
+    step: Step,
+    shows_header: bool,
+    /// The text and width of the cells of each row to show, restarted for
+    /// every group.
+    rows: R,
+    current: Option<R>,
+    footer: Option<String>,
+}
+
+impl<'a, T, const N: usize, R, C> PlainLines<'a, T, N, R>
+    where R: Iterator<Item = C> + Clone,
+          C: IntoIterator<Item = (Cow<'a, str>, usize)> {
+    /// Lay out `rows`, the shown rows out of `row_count`, at `widths`, in
+    /// `groups` of columns if the table is too wide to show them side by side.
+    pub(crate) fn new(table: &'a Table<T, N>, rows: R, row_count: usize, widths: Vec<usize>, groups: Option<Vec<Vec<usize>>>) -> Self {
+        let groups = match groups {
+            Some(groups) => groups.iter()
+                .map(|columns| {
+                    let mut shown = vec![false; widths.len()];
+                    for &c in columns {
+                        shown[c] = true;
+                    }
+                    shown
+                })
+                .collect(),
+            None => vec![vec![true; widths.len()]],
+        };
+        PlainLines {
+            footer: table.footer_for(row_count, &widths),
+            shows_header: table.shows_header(row_count),
+            table,
+            widths,
+            groups,
+            group: 0,
+            step: Step::Gap,
+            rows,
+            current: None,
+        }
+    }
+
+    /// Write the next line, with its line ending, to `f`. Returns `None` once
+    /// every line has been written.
+    pub(crate) fn write_next<W: Write>(&mut self, f: &mut W) -> Option<core::fmt::Result> {
+        loop {
+            match self.step {
+                Step::Gap => {
+                    self.step = Step::Header;
+                    if self.group > 0 {
+                        return Some(writeln!(f));
+                    }
+                }
+                Step::Header => {
+                    self.step = Step::Rows;
+                    self.current = Some(self.rows.clone());
+                    if self.shows_header {
+                        return Some(self.write_header(f));
+                    }
+                }
+                Step::Rows => match self.current.as_mut().and_then(Iterator::next) {
+                    Some(texts) => return Some(self.write_row(f, texts)),
+                    None => {
+                        self.group += 1;
+                        self.step = if self.group < self.groups.len() { Step::Gap } else { Step::Footer };
+                    }
+                },
+                Step::Footer => {
+                    self.step = Step::Done;
+                    if let Some(footer) = self.footer.take() {
+                        return Some(writeln!(f, "{footer}"));
+                    }
+                }
+                Step::Done => return None,
+            }
+        }
+    }
+
+    fn write_header<W: Write>(&self, f: &mut W) -> core::fmt::Result {
+        let shown = &self.groups[self.group];
+        for (header, width) in self.table.headers.iter()
+            .zip(self.widths.iter())
+            .zip(shown)
+            .filter_map(|(column, shown)| shown.then_some(column)) {
+            let strategy = self.table.config.width_strategy;
+            write_padded(f, &header.text, crate::width(&header.text, strategy), *width, header.alignment, strategy)?;
+            f.write_char(' ')?;
+        }
+        writeln!(f)
+    }
+
+    fn write_row<W: Write>(&self, f: &mut W, texts: C) -> core::fmt::Result {
+        let shown = &self.groups[self.group];
+        for (i, ((text, visible), width)) in texts.into_iter()
+            .zip(self.widths.iter())
+            .enumerate()
+            .filter(|(i, _)| shown[*i]) {
+            write_padded(f, &text, visible, *width, self.table.cell_alignment(i), self.table.config.width_strategy)?;
+            f.write_char(' ')?;
+        }
+        writeln!(f)
+    }
+}
+
+impl<'a, T, const N: usize, R, C> Iterator for PlainLines<'a, T, N, R>
+    where R: Iterator<Item = C> + Clone,
+          C: IntoIterator<Item = (Cow<'a, str>, usize)> {
+    type Item = String;
+
+    /// The next line, without its line ending.
+    fn next(&mut self) -> Option<String> {
+        let mut line = String::new();
+        self.write_next(&mut line)?.expect("writing to a String can't fail");
+        line.pop();
+        Some(line)
+    }
+}
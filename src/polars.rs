@@ -0,0 +1,92 @@
+use polars::prelude::{AnyValue, DataFrame, DataType};
+
+use crate::{Cell, ColumnType, Config, Header, ModifyRows, Table};
+
+impl<const N: usize> Table<ModifyRows, N> {
+    /// Preview a data frame. Frames taller than `max_rows` show their first and
+    /// last rows around a row of `…`. Numeric and boolean columns are typed, so
+    /// numbers are right-aligned.
+    pub fn from_dataframe(frame: &DataFrame, max_rows: usize) -> Self {
+        let columns = frame.columns();
+        let headers: Vec<Header> = columns.iter()
+            .map(|column| Header {
+                text: column.name().to_string(),
+                column_type: column_type(column.dtype()),
+                ..Header::default()
+            })
+            .collect();
+
+        let height = frame.height();
+        let row = |i: usize| -> Vec<Cell> {
+            columns.iter()
+                .map(|column| column.get(i).map(cell).unwrap_or_default())
+                .collect()
+        };
+        let rows = if height <= max_rows {
+            (0..height).map(row).collect()
+        } else {
+            let head = max_rows.div_ceil(2);
+            let tail = max_rows - head;
+            let mut rows: Vec<Vec<Cell>> = (0..head).map(row).collect();
+            rows.push(vec![Cell::from("…"); columns.len()]);
+            rows.extend((height - tail..height).map(row));
+            rows
+        };
+        Table::from_parts(headers, rows, Config::default())
+    }
+}
+
+fn column_type(dtype: &DataType) -> Option<ColumnType> {
+    if dtype.is_integer() {
+        Some(ColumnType::Int)
+    } else if dtype.is_float() {
+        Some(ColumnType::Float)
+    } else if dtype.is_bool() {
+        Some(ColumnType::Bool)
+    } else {
+        None
+    }
+}
+
+fn cell(value: AnyValue) -> Cell {
+    match value {
+        AnyValue::Null => Cell::Empty,
+        AnyValue::Boolean(b) => b.into(),
+        AnyValue::String(s) => s.into(),
+        AnyValue::StringOwned(s) => s.as_str().into(),
+        AnyValue::UInt8(n) => n.into(),
+        AnyValue::UInt16(n) => n.into(),
+        AnyValue::UInt32(n) => n.into(),
+        AnyValue::UInt64(n) => n.into(),
+        AnyValue::Int8(n) => n.into(),
+        AnyValue::Int16(n) => n.into(),
+        AnyValue::Int32(n) => n.into(),
+        AnyValue::Int64(n) => n.into(),
+        AnyValue::Float32(n) => n.into(),
+        AnyValue::Float64(n) => n.into(),
+        other => other.to_string().into(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use polars::prelude::{Column, DataFrame};
+
+    use crate::{tests::texts, ModifyRows, Table};
+
+    #[test]
+    fn test_from_dataframe() {
+        let frame = DataFrame::new_infer_height(vec![
+            Column::new("city".into(), ["Oslo", "Rome", "Lima", "Pune", "Kyiv"]),
+            Column::new("pop".into(), [0.7, 2.8, 10.0, 3.1, 2.9]),
+        ]).unwrap();
+        let table: Table<ModifyRows> = Table::from_dataframe(&frame, 3);
+        assert_eq!(texts(&table), vec![
+            vec!["Oslo", "0.7"],
+            vec!["Rome", "2.8"],
+            vec!["…", "…"],
+            vec!["Kyiv", "2.9"],
+        ]);
+        assert!(table.to_string().starts_with("city     pop      \nOslo          0.7 \n"));
+    }
+}
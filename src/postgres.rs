@@ -0,0 +1,173 @@
+use std::error::Error;
+use std::fmt::Write;
+
+use chrono::{DateTime, NaiveDate, NaiveDateTime, NaiveTime, Utc};
+use tokio_postgres::types::{FromSql, Kind, Type};
+use tokio_postgres::Row;
+
+use crate::{Cell, Config, Header, ModifyRows, Table};
+
+impl<const N: usize> Table<ModifyRows, N> {
+    /// Build a table from query results, with a header per result column.
+    ///
+    /// Numbers, booleans and text keep their type. Timestamps, dates and
+    /// `NUMERIC` values are shown as text, arrays in Postgres' `{a,b}` notation,
+    /// and columns of other types as `<type name>`. NULL becomes an empty cell.
+    pub fn from_postgres_rows(rows: &[Row]) -> Result<Self, tokio_postgres::Error> {
+        let headers: Vec<Header> = match rows.first() {
+            Some(row) => row.columns().iter().map(|c| c.name().into()).collect(),
+            None => Vec::new(),
+        };
+        let rows = rows.iter()
+            .map(|row| (0..row.len()).map(|i| cell(row, i)).collect())
+            .collect::<Result<Vec<Vec<Cell>>, _>>()?;
+        Ok(Table::from_parts(headers, rows, Config::default()))
+    }
+}
+
+fn cell(row: &Row, index: usize) -> Result<Cell, tokio_postgres::Error> {
+    let ty = row.columns()[index].type_();
+    if let Kind::Array(element) = ty.kind() {
+        return array(row, index, element);
+    }
+    Ok(match *ty {
+        Type::BOOL => row.try_get::<_, Option<bool>>(index)?.into(),
+        Type::CHAR => row.try_get::<_, Option<i8>>(index)?.into(),
+        Type::INT2 => row.try_get::<_, Option<i16>>(index)?.into(),
+        Type::INT4 => row.try_get::<_, Option<i32>>(index)?.into(),
+        Type::INT8 => row.try_get::<_, Option<i64>>(index)?.into(),
+        Type::OID => row.try_get::<_, Option<u32>>(index)?.into(),
+        Type::FLOAT4 => row.try_get::<_, Option<f32>>(index)?.into(),
+        Type::FLOAT8 => row.try_get::<_, Option<f64>>(index)?.into(),
+        Type::NUMERIC => row.try_get::<_, Option<Numeric>>(index)?.map(|n| n.0).into(),
+        Type::TIMESTAMP => text::<NaiveDateTime>(row, index)?,
+        Type::TIMESTAMPTZ => text::<DateTime<Utc>>(row, index)?,
+        Type::DATE => text::<NaiveDate>(row, index)?,
+        Type::TIME => text::<NaiveTime>(row, index)?,
+        _ if <String as FromSql>::accepts(ty) => row.try_get::<_, Option<String>>(index)?.into(),
+        _ => format!("<{}>", ty.name()).into(),
+    })
+}
+
+fn text<T>(row: &Row, index: usize) -> Result<Cell, tokio_postgres::Error>
+where
+    T: for<'a> FromSql<'a> + ToString,
+{
+    Ok(row.try_get::<_, Option<T>>(index)?.map(|v| v.to_string()).into())
+}
+
+fn array(row: &Row, index: usize, element: &Type) -> Result<Cell, tokio_postgres::Error> {
+    match *element {
+        Type::BOOL => join::<bool>(row, index),
+        Type::INT2 => join::<i16>(row, index),
+        Type::INT4 => join::<i32>(row, index),
+        Type::INT8 => join::<i64>(row, index),
+        Type::FLOAT4 => join::<f32>(row, index),
+        Type::FLOAT8 => join::<f64>(row, index),
+        Type::NUMERIC => join::<Numeric>(row, index),
+        Type::TIMESTAMP => join::<NaiveDateTime>(row, index),
+        Type::TIMESTAMPTZ => join::<DateTime<Utc>>(row, index),
+        Type::DATE => join::<NaiveDate>(row, index),
+        _ if <String as FromSql>::accepts(element) => join::<String>(row, index),
+        _ => Ok(format!("<{}[]>", element.name()).into()),
+    }
+}
+
+fn join<T>(row: &Row, index: usize) -> Result<Cell, tokio_postgres::Error>
+where
+    T: for<'a> FromSql<'a> + ToString,
+{
+    let Some(values) = row.try_get::<_, Option<Vec<Option<T>>>>(index)? else {
+        return Ok(Cell::Empty);
+    };
+    let values: Vec<String> = values.into_iter()
+        .map(|v| v.map(|v| v.to_string()).unwrap_or_else(|| "NULL".to_string()))
+        .collect();
+    Ok(format!("{{{}}}", values.join(",")).into())
+}
+
+/// A `NUMERIC` value as its exact decimal text.
+struct Numeric(String);
+
+impl std::fmt::Display for Numeric {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+impl<'a> FromSql<'a> for Numeric {
+    /// The binary format is a header of digit count, weight, sign and display
+    /// scale, followed by base-10000 digits.
+    fn from_sql(_: &Type, raw: &'a [u8]) -> Result<Self, Box<dyn Error + Sync + Send>> {
+        let word = |i: usize| -> Result<u16, Box<dyn Error + Sync + Send>> {
+            raw.get(i * 2..i * 2 + 2)
+                .map(|b| u16::from_be_bytes([b[0], b[1]]))
+                .ok_or_else(|| "truncated NUMERIC value".into())
+        };
+        let ndigits = word(0)? as usize;
+        let weight = word(1)? as i16 as isize;
+        let sign = word(2)?;
+        let dscale = word(3)? as usize;
+        let digits = (0..ndigits).map(|i| word(4 + i)).collect::<Result<Vec<_>, _>>()?;
+        let digit = |i: isize| if i >= 0 { digits.get(i as usize).copied().unwrap_or(0) } else { 0 };
+
+        match sign {
+            0xC000 => return Ok(Numeric("NaN".to_string())),
+            0xD000 => return Ok(Numeric("Infinity".to_string())),
+            0xF000 => return Ok(Numeric("-Infinity".to_string())),
+            _ => {}
+        }
+        let mut text = String::new();
+        if sign == 0x4000 {
+            text.push('-');
+        }
+        if weight < 0 {
+            text.push('0');
+        }
+        for i in 0..=weight {
+            if i == 0 {
+                write!(text, "{}", digit(i))?;
+            } else {
+                write!(text, "{:04}", digit(i))?;
+            }
+        }
+        if dscale > 0 {
+            let mut fraction = String::new();
+            let mut i = weight + 1;
+            while fraction.len() < dscale {
+                write!(fraction, "{:04}", digit(i))?;
+                i += 1;
+            }
+            fraction.truncate(dscale);
+            text.push('.');
+            text.push_str(&fraction);
+        }
+        Ok(Numeric(text))
+    }
+
+    fn accepts(ty: &Type) -> bool {
+        *ty == Type::NUMERIC
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use tokio_postgres::types::{FromSql, Type};
+
+    use super::Numeric;
+
+    fn numeric(words: &[u16]) -> String {
+        let raw: Vec<u8> = words.iter().flat_map(|w| w.to_be_bytes()).collect();
+        Numeric::from_sql(&Type::NUMERIC, &raw).unwrap().0
+    }
+
+    #[test]
+    fn test_numeric() {
+        // 12345.678: digits 1, 2345, 6780 with weight 1 and scale 3.
+        assert_eq!(numeric(&[3, 1, 0, 3, 1, 2345, 6780]), "12345.678");
+        // -0.0042: one digit 42 with weight -1 and scale 4.
+        assert_eq!(numeric(&[1, 0xFFFF, 0x4000, 4, 42]), "-0.0042");
+        assert_eq!(numeric(&[0, 0, 0, 2]), "0.00");
+        assert_eq!(numeric(&[0, 0, 0xC000, 0]), "NaN");
+    }
+}
@@ -0,0 +1,34 @@
+use crate::{Cell, Config, ModifyRows, Table};
+
+/// Copies the cell contents. `prettytable` doesn't expose a table's titles, so
+/// the result has no headers.
+impl<const N: usize> From<&prettytable::Table> for Table<ModifyRows, N> {
+    fn from(table: &prettytable::Table) -> Self {
+        let rows = table.row_iter()
+            .map(|row| row.iter().map(|cell| Cell::from(cell.get_content())).collect())
+            .collect();
+        Table::from_parts(Vec::new(), rows, Config::default())
+    }
+}
+
+impl<const N: usize> From<prettytable::Table> for Table<ModifyRows, N> {
+    fn from(table: prettytable::Table) -> Self {
+        Table::from(&table)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use prettytable::{Cell, Row};
+
+    use crate::{tests::texts, ModifyRows, Table};
+
+    #[test]
+    fn test_from_prettytable() {
+        let mut source = prettytable::Table::new();
+        source.add_row(Row::new(vec![Cell::new("web-1"), Cell::new("up")]));
+        source.add_row(Row::new(vec![Cell::new("db-1"), Cell::new("down")]));
+        let table: Table<ModifyRows> = Table::from(source);
+        assert_eq!(texts(&table), vec![vec!["web-1", "up"], vec!["db-1", "down"]]);
+    }
+}
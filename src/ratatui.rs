@@ -0,0 +1,58 @@
+use ratatui::layout::{Alignment as TuiAlignment, Constraint};
+use ratatui::text::Line;
+use ratatui::widgets::{Cell as TuiCell, Row as TuiRow, Table as TuiTable};
+
+use crate::{Alignment, Table};
+
+impl<T, const N: usize> Table<T, N> {
+    /// Convert to a ratatui table widget with the same headers, rows, column
+    /// widths and alignments. ANSI styling is dropped, so style the widget
+    /// through ratatui instead.
+    pub fn to_ratatui(&self) -> TuiTable<'static> {
+        let widths: Vec<Constraint> = (0..self.column_widths.len())
+            .map(|i| Constraint::Length(self.display_width(i) as u16))
+            .collect();
+        let rows = self.rows.iter().map(|row| {
+            TuiRow::new(row.iter().enumerate().map(|(i, cell)| tui_cell(&self.shown_text(i, cell), self.cell_alignment(i))))
+        });
+        let mut table = TuiTable::new(rows, widths);
+        if !self.config.skip_header && !self.headers.is_empty() {
+            table = table.header(TuiRow::new(self.headers.iter().map(|h| tui_cell(&h.text, h.alignment))));
+        }
+        table
+    }
+}
+
+fn tui_cell(text: &str, alignment: Alignment) -> TuiCell<'static> {
+    let plain = crate::ansi::strip(text);
+    let alignment = match alignment {
+        Alignment::Left => TuiAlignment::Left,
+        Alignment::Right => TuiAlignment::Right,
+        Alignment::Center => TuiAlignment::Center,
+    };
+    TuiCell::from(Line::from(String::from_utf8_lossy(&plain).into_owned()).alignment(alignment))
+}
+
+#[cfg(test)]
+mod tests {
+    use ratatui::buffer::Buffer;
+    use ratatui::layout::Rect;
+    use ratatui::widgets::Widget;
+
+    use crate::{Alignment, Column, ModifyRows, Row, Table};
+
+    #[test]
+    fn test_to_ratatui() {
+        let table: Table<ModifyRows> = Table::new()
+            .header("Name")
+            .header(Column::new("Count").align(Alignment::Right))
+            .row(Row::new().cell("apples").cell(3));
+        let area = Rect::new(0, 0, 20, 2);
+        let mut buffer = Buffer::empty(area);
+        table.to_ratatui().render(area, &mut buffer);
+        assert_eq!(buffer, Buffer::with_lines([
+            "Name        Count   ",
+            "apples          3   ",
+        ]));
+    }
+}
@@ -0,0 +1,49 @@
+use rayon::prelude::*;
+
+use crate::{count_empty, widen, ModifyRows, Row, RowMeta, Table};
+
+impl<const N: usize> Table<ModifyRows, N> {
+    /// Like [`extend`](Extend::extend), but measures the new cells on all
+    /// cores. Worth it for very large loads, where measuring text dominates.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the row length policy rejects a row, or if a row sets a cell
+    /// under a header the table doesn't have.
+    pub fn par_extend_rows<I: IntoIterator<Item = Row<N>>>(&mut self, rows: I) {
+        let start = self.rows.len();
+        for row in rows {
+            let (cells, tag) = self.prepare(row).unwrap_or_else(|e| panic!("{e}"));
+            count_empty(&mut self.empty_cells, &cells);
+            self.rows.push(cells);
+            self.meta.push(RowMeta { widths: Vec::new(), tag });
+        }
+        let rows = &self.rows;
+        let headers = &self.headers;
+        let strategy = self.config.width_strategy;
+        self.meta[start..].par_iter_mut()
+            .enumerate()
+            .for_each(|(i, meta)| meta.widths = RowMeta::measure(headers, &rows[start + i], strategy).widths);
+        if self.config.fixed_widths.is_some() {
+            return;
+        }
+        let headerless = self.headers.is_empty();
+        for meta in self.meta[start..].iter() {
+            widen(&mut self.column_widths, headerless, &meta.widths);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{Row, Table};
+
+    #[test]
+    fn test_par_extend_rows() {
+        let mut table = Table::new().header("Id").header("Name").end_header();
+        table.par_extend_rows((0..1000).map(|i| Row::new().cell(i).cell(format!("row {i}"))));
+        let mut expected = Table::new().header("Id").header("Name").end_header();
+        expected.extend((0..1000).map(|i| Row::new().cell(i).cell(format!("row {i}"))));
+        assert_eq!(table, expected);
+    }
+}
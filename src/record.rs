@@ -0,0 +1,106 @@
+use alloc::string::{String, ToString};
+use alloc::vec;
+use alloc::vec::Vec;
+
+use crate::{Cell, Config, Header, ModifyRows, Row, Table};
+
+/// Types that can be turned into table rows, usually through `#[derive(Tabular)]`
+/// with the `derive` feature.
+pub trait Tabular {
+    /// The headers of a table holding values of this type.
+    fn headers() -> Vec<Header>;
+
+    /// The cells of this value, in the same order as the headers.
+    fn into_row<const N: usize>(self) -> Row<N>;
+}
+
+impl<const N: usize> Table<ModifyRows, N> {
+    /// Build a table from values implementing [`Tabular`], taking the headers from the type.
+    pub fn from_records<R: Tabular, I: IntoIterator<Item = R>>(records: I) -> Self {
+        let mut table = R::headers()
+            .into_iter()
+            .fold(Table::default(), |table, header| table.header(header))
+            .end_header();
+        table.extend(records.into_iter().map(R::into_row));
+        table
+    }
+}
+
+/// Lay out records of `(field, value)` pairs as rows. Headers are the union of the field names, in
+/// the order they are first seen, and records missing a field get an empty cell.
+pub(crate) fn union_of_fields<I: IntoIterator<Item = Vec<(String, Cell)>>>(records: I) -> (Vec<Header>, Vec<Vec<Cell>>) {
+    let mut headers: Vec<String> = Vec::new();
+    let mut rows = Vec::new();
+    for fields in records {
+        let mut cells = vec![Cell::Empty; headers.len()];
+        for (key, value) in fields {
+            let index = match headers.iter().position(|h| *h == key) {
+                Some(i) => i,
+                None => {
+                    headers.push(key);
+                    cells.push(Cell::Empty);
+                    headers.len() - 1
+                }
+            };
+            cells[index] = value;
+        }
+        rows.push(cells);
+    }
+    for cells in rows.iter_mut() {
+        cells.resize(headers.len(), Cell::Empty);
+    }
+    (headers.into_iter().map(Into::into).collect(), rows)
+}
+
+impl<const N: usize> Table<ModifyRows, N> {
+    /// Build a two-column Key/Value table from a map, or any iterator of pairs.
+    pub fn from_map<I, K, V>(map: I) -> Self
+    where
+        I: IntoIterator<Item = (K, V)>,
+        K: Into<Cell>,
+        V: Into<Cell>,
+    {
+        let rows = map.into_iter()
+            .map(|(k, v)| vec![k.into(), v.into()])
+            .collect();
+        Table::from_parts(vec!["Key".into(), "Value".into()], rows, Config::default())
+    }
+
+    /// Build a table with one row per map. Headers are the union of the keys,
+    /// in the order they are first seen, and maps missing a key get an empty cell.
+    pub fn from_maps<I, M, K, V>(maps: I) -> Self
+    where
+        I: IntoIterator<Item = M>,
+        M: IntoIterator<Item = (K, V)>,
+        K: ToString,
+        V: Into<Cell>,
+    {
+        let records = maps.into_iter()
+            .map(|map| map.into_iter().map(|(k, v)| (k.to_string(), v.into())).collect());
+        let (headers, rows) = union_of_fields(records);
+        Table::from_parts(headers, rows, Config::default())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use alloc::collections::BTreeMap;
+
+    use crate::{tests::texts, ModifyRows, Table};
+
+    #[test]
+    fn test_from_maps() {
+        let env = BTreeMap::from([("HOME", "/root"), ("SHELL", "/bin/sh")]);
+        let table: Table<ModifyRows> = Table::from_map(env);
+        assert_eq!(table.to_string(),
+                   "Key      Value    \n".to_owned() +
+                       "HOME     /root    \n" +
+                       "SHELL    /bin/sh  \n");
+
+        let table: Table<ModifyRows> = Table::from_maps(vec![
+            BTreeMap::from([("name", "a"), ("zone", "east")]),
+            BTreeMap::from([("name", "b"), ("owner", "ops")]),
+        ]);
+        assert_eq!(texts(&table), vec![vec!["a", "east", ""], vec!["b", "", "ops"]]);
+    }
+}
@@ -0,0 +1,378 @@
+use serde::ser::{self, Impossible, Serialize, SerializeMap, SerializeStruct};
+
+use crate::record::union_of_fields;
+use crate::{Cell, Config, ModifyRows, Table};
+
+/// Returned when a value can't be turned into a table row.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RecordError(pub(crate) String);
+
+impl std::fmt::Display for RecordError {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+impl std::error::Error for RecordError {}
+
+impl ser::Error for RecordError {
+    fn custom<T: std::fmt::Display>(msg: T) -> Self {
+        RecordError(msg.to_string())
+    }
+}
+
+fn unsupported<T>(what: &str) -> Result<T, RecordError> {
+    Err(RecordError(format!("{what} can't be stored in a table cell")))
+}
+
+impl<const N: usize> Table<ModifyRows, N> {
+    /// Build a table from flat structs or maps implementing `serde::Serialize`. Headers are the
+    /// union of the field names, in the order they are first seen, and records missing a field get
+    /// an empty cell. Fields holding sequences, maps or nested structs are an error.
+    pub fn from_serialize<R: Serialize, I: IntoIterator<Item = R>>(records: I) -> Result<Self, RecordError> {
+        let records = records.into_iter()
+            .map(|record| record.serialize(RecordSerializer))
+            .collect::<Result<Vec<_>, _>>()?;
+        let (headers, rows) = union_of_fields(records);
+        Ok(Table::from_parts(headers, rows, Config::default()))
+    }
+}
+
+/// Serializes a struct or map into its `(field, cell)` pairs.
+struct RecordSerializer;
+
+struct Fields {
+    fields: Vec<(String, Cell)>,
+    key: Option<String>,
+}
+
+impl ser::Serializer for RecordSerializer {
+    type Ok = Vec<(String, Cell)>;
+    type Error = RecordError;
+    type SerializeSeq = Impossible<Self::Ok, RecordError>;
+    type SerializeTuple = Impossible<Self::Ok, RecordError>;
+    type SerializeTupleStruct = Impossible<Self::Ok, RecordError>;
+    type SerializeTupleVariant = Impossible<Self::Ok, RecordError>;
+    type SerializeMap = Fields;
+    type SerializeStruct = Fields;
+    type SerializeStructVariant = Impossible<Self::Ok, RecordError>;
+
+    fn serialize_bool(self, _: bool) -> Result<Self::Ok, RecordError> {
+        unsupported("a record that isn't a struct or map")
+    }
+
+    fn serialize_i8(self, _: i8) -> Result<Self::Ok, RecordError> {
+        unsupported("a record that isn't a struct or map")
+    }
+
+    fn serialize_i16(self, _: i16) -> Result<Self::Ok, RecordError> {
+        unsupported("a record that isn't a struct or map")
+    }
+
+    fn serialize_i32(self, _: i32) -> Result<Self::Ok, RecordError> {
+        unsupported("a record that isn't a struct or map")
+    }
+
+    fn serialize_i64(self, _: i64) -> Result<Self::Ok, RecordError> {
+        unsupported("a record that isn't a struct or map")
+    }
+
+    fn serialize_u8(self, _: u8) -> Result<Self::Ok, RecordError> {
+        unsupported("a record that isn't a struct or map")
+    }
+
+    fn serialize_u16(self, _: u16) -> Result<Self::Ok, RecordError> {
+        unsupported("a record that isn't a struct or map")
+    }
+
+    fn serialize_u32(self, _: u32) -> Result<Self::Ok, RecordError> {
+        unsupported("a record that isn't a struct or map")
+    }
+
+    fn serialize_u64(self, _: u64) -> Result<Self::Ok, RecordError> {
+        unsupported("a record that isn't a struct or map")
+    }
+
+    fn serialize_f32(self, _: f32) -> Result<Self::Ok, RecordError> {
+        unsupported("a record that isn't a struct or map")
+    }
+
+    fn serialize_f64(self, _: f64) -> Result<Self::Ok, RecordError> {
+        unsupported("a record that isn't a struct or map")
+    }
+
+    fn serialize_char(self, _: char) -> Result<Self::Ok, RecordError> {
+        unsupported("a record that isn't a struct or map")
+    }
+
+    fn serialize_str(self, _: &str) -> Result<Self::Ok, RecordError> {
+        unsupported("a record that isn't a struct or map")
+    }
+
+    fn serialize_bytes(self, _: &[u8]) -> Result<Self::Ok, RecordError> {
+        unsupported("a record that isn't a struct or map")
+    }
+
+    fn serialize_none(self) -> Result<Self::Ok, RecordError> {
+        Ok(Vec::new())
+    }
+
+    fn serialize_some<T: ?Sized + Serialize>(self, value: &T) -> Result<Self::Ok, RecordError> {
+        value.serialize(self)
+    }
+
+    fn serialize_unit(self) -> Result<Self::Ok, RecordError> {
+        Ok(Vec::new())
+    }
+
+    fn serialize_unit_struct(self, _: &'static str) -> Result<Self::Ok, RecordError> {
+        Ok(Vec::new())
+    }
+
+    fn serialize_unit_variant(self, _: &'static str, _: u32, _: &'static str) -> Result<Self::Ok, RecordError> {
+        unsupported("a record that isn't a struct or map")
+    }
+
+    fn serialize_newtype_struct<T: ?Sized + Serialize>(self, _: &'static str, value: &T) -> Result<Self::Ok, RecordError> {
+        value.serialize(self)
+    }
+
+    fn serialize_newtype_variant<T: ?Sized + Serialize>(self, _: &'static str, _: u32, _: &'static str, value: &T) -> Result<Self::Ok, RecordError> {
+        value.serialize(self)
+    }
+
+    fn serialize_seq(self, _: Option<usize>) -> Result<Self::SerializeSeq, RecordError> {
+        unsupported("a record that isn't a struct or map")
+    }
+
+    fn serialize_tuple(self, _: usize) -> Result<Self::SerializeTuple, RecordError> {
+        unsupported("a record that isn't a struct or map")
+    }
+
+    fn serialize_tuple_struct(self, _: &'static str, _: usize) -> Result<Self::SerializeTupleStruct, RecordError> {
+        unsupported("a record that isn't a struct or map")
+    }
+
+    fn serialize_tuple_variant(self, _: &'static str, _: u32, _: &'static str, _: usize) -> Result<Self::SerializeTupleVariant, RecordError> {
+        unsupported("a record that isn't a struct or map")
+    }
+
+    fn serialize_map(self, len: Option<usize>) -> Result<Fields, RecordError> {
+        Ok(Fields {
+            fields: Vec::with_capacity(len.unwrap_or(0)),
+            key: None,
+        })
+    }
+
+    fn serialize_struct(self, _: &'static str, len: usize) -> Result<Fields, RecordError> {
+        Ok(Fields {
+            fields: Vec::with_capacity(len),
+            key: None,
+        })
+    }
+
+    fn serialize_struct_variant(self, _: &'static str, _: u32, _: &'static str, _: usize) -> Result<Self::SerializeStructVariant, RecordError> {
+        unsupported("a record that isn't a struct or map")
+    }
+}
+
+impl SerializeStruct for Fields {
+    type Ok = Vec<(String, Cell)>;
+    type Error = RecordError;
+
+    fn serialize_field<T: ?Sized + Serialize>(&mut self, key: &'static str, value: &T) -> Result<(), RecordError> {
+        self.fields.push((key.to_string(), value.serialize(CellSerializer)?));
+        Ok(())
+    }
+
+    fn end(self) -> Result<Self::Ok, RecordError> {
+        Ok(self.fields)
+    }
+}
+
+impl SerializeMap for Fields {
+    type Ok = Vec<(String, Cell)>;
+    type Error = RecordError;
+
+    fn serialize_key<T: ?Sized + Serialize>(&mut self, key: &T) -> Result<(), RecordError> {
+        self.key = Some(key.serialize(CellSerializer)?.to_string());
+        Ok(())
+    }
+
+    fn serialize_value<T: ?Sized + Serialize>(&mut self, value: &T) -> Result<(), RecordError> {
+        let key = self.key.take().expect("serialize_value called before serialize_key");
+        self.fields.push((key, value.serialize(CellSerializer)?));
+        Ok(())
+    }
+
+    fn end(self) -> Result<Self::Ok, RecordError> {
+        Ok(self.fields)
+    }
+}
+
+/// Serializes a scalar into a [`Cell`].
+struct CellSerializer;
+
+impl ser::Serializer for CellSerializer {
+    type Ok = Cell;
+    type Error = RecordError;
+    type SerializeSeq = Impossible<Cell, RecordError>;
+    type SerializeTuple = Impossible<Cell, RecordError>;
+    type SerializeTupleStruct = Impossible<Cell, RecordError>;
+    type SerializeTupleVariant = Impossible<Cell, RecordError>;
+    type SerializeMap = Impossible<Cell, RecordError>;
+    type SerializeStruct = Impossible<Cell, RecordError>;
+    type SerializeStructVariant = Impossible<Cell, RecordError>;
+
+    fn serialize_bool(self, v: bool) -> Result<Cell, RecordError> {
+        Ok(v.into())
+    }
+
+    fn serialize_i8(self, v: i8) -> Result<Cell, RecordError> {
+        Ok(v.into())
+    }
+
+    fn serialize_i16(self, v: i16) -> Result<Cell, RecordError> {
+        Ok(v.into())
+    }
+
+    fn serialize_i32(self, v: i32) -> Result<Cell, RecordError> {
+        Ok(v.into())
+    }
+
+    fn serialize_i64(self, v: i64) -> Result<Cell, RecordError> {
+        Ok(v.into())
+    }
+
+    fn serialize_u8(self, v: u8) -> Result<Cell, RecordError> {
+        Ok(v.into())
+    }
+
+    fn serialize_u16(self, v: u16) -> Result<Cell, RecordError> {
+        Ok(v.into())
+    }
+
+    fn serialize_u32(self, v: u32) -> Result<Cell, RecordError> {
+        Ok(v.into())
+    }
+
+    fn serialize_u64(self, v: u64) -> Result<Cell, RecordError> {
+        Ok(v.into())
+    }
+
+    fn serialize_f32(self, v: f32) -> Result<Cell, RecordError> {
+        Ok(v.into())
+    }
+
+    fn serialize_f64(self, v: f64) -> Result<Cell, RecordError> {
+        Ok(v.into())
+    }
+
+    fn serialize_char(self, v: char) -> Result<Cell, RecordError> {
+        Ok(v.to_string().into())
+    }
+
+    fn serialize_str(self, v: &str) -> Result<Cell, RecordError> {
+        Ok(v.into())
+    }
+
+    fn serialize_bytes(self, _: &[u8]) -> Result<Cell, RecordError> {
+        unsupported("bytes")
+    }
+
+    fn serialize_none(self) -> Result<Cell, RecordError> {
+        Ok(Cell::Empty)
+    }
+
+    fn serialize_some<T: ?Sized + Serialize>(self, value: &T) -> Result<Cell, RecordError> {
+        value.serialize(self)
+    }
+
+    fn serialize_unit(self) -> Result<Cell, RecordError> {
+        Ok(Cell::Empty)
+    }
+
+    fn serialize_unit_struct(self, _: &'static str) -> Result<Cell, RecordError> {
+        Ok(Cell::Empty)
+    }
+
+    fn serialize_unit_variant(self, _: &'static str, _: u32, variant: &'static str) -> Result<Cell, RecordError> {
+        Ok(variant.into())
+    }
+
+    fn serialize_newtype_struct<T: ?Sized + Serialize>(self, _: &'static str, value: &T) -> Result<Cell, RecordError> {
+        value.serialize(self)
+    }
+
+    fn serialize_newtype_variant<T: ?Sized + Serialize>(self, _: &'static str, _: u32, _: &'static str, _: &T) -> Result<Cell, RecordError> {
+        unsupported("an enum variant with data")
+    }
+
+    fn serialize_seq(self, _: Option<usize>) -> Result<Self::SerializeSeq, RecordError> {
+        unsupported("a sequence")
+    }
+
+    fn serialize_tuple(self, _: usize) -> Result<Self::SerializeTuple, RecordError> {
+        unsupported("a tuple")
+    }
+
+    fn serialize_tuple_struct(self, _: &'static str, _: usize) -> Result<Self::SerializeTupleStruct, RecordError> {
+        unsupported("a tuple struct")
+    }
+
+    fn serialize_tuple_variant(self, _: &'static str, _: u32, _: &'static str, _: usize) -> Result<Self::SerializeTupleVariant, RecordError> {
+        unsupported("an enum variant with data")
+    }
+
+    fn serialize_map(self, _: Option<usize>) -> Result<Self::SerializeMap, RecordError> {
+        unsupported("a map")
+    }
+
+    fn serialize_struct(self, _: &'static str, _: usize) -> Result<Self::SerializeStruct, RecordError> {
+        unsupported("a nested struct")
+    }
+
+    fn serialize_struct_variant(self, _: &'static str, _: u32, _: &'static str, _: usize) -> Result<Self::SerializeStructVariant, RecordError> {
+        unsupported("an enum variant with data")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeMap;
+
+    use serde::Serialize;
+
+    use crate::{tests::texts, HeaderCase, ModifyRows, Table};
+
+    #[test]
+    fn test_from_serialize() {
+        #[derive(Serialize)]
+        struct Server {
+            name: &'static str,
+            cpu: f32,
+            tags: Option<&'static str>,
+        }
+
+        let table: Table<ModifyRows> = Table::from_serialize(vec![
+            Server { name: "web-1", cpu: 0.5, tags: Some("prod") },
+            Server { name: "db-1", cpu: 2.0, tags: None },
+        ]).unwrap();
+        assert_eq!(table.headers().iter().map(|h| h.text.as_str()).collect::<Vec<_>>(), vec!["name", "cpu", "tags"]);
+        assert_eq!(texts(&table), vec![vec!["web-1", "0.5", "prod"], vec!["db-1", "2", ""]]);
+
+        let maps = vec![
+            BTreeMap::from([("a", 1)]),
+            BTreeMap::from([("b", 2)]),
+        ];
+        let table: Table<ModifyRows> = Table::from_serialize(maps).unwrap();
+        assert_eq!(texts(&table), vec![vec!["1", ""], vec!["", "2"]]);
+
+        let table: Table<ModifyRows> = Table::from_serialize(vec![BTreeMap::from([("disk_used", 1)])])
+            .unwrap()
+            .header_case(HeaderCase::Title);
+        assert_eq!(table.headers()[0].text, "Disk Used");
+
+        assert!(Table::<ModifyRows>::from_serialize(vec![vec![1]]).is_err());
+    }
+}
@@ -0,0 +1,264 @@
+use alloc::vec::Vec;
+use core::ops::Index;
+
+use crate::Cell;
+
+/// The rows of a table, stored in one flat `Vec` with the offset where each
+/// row ends. Rows are usually all as long as the headers, but a table without
+/// headers may have rows of different lengths.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub(crate) struct Rows {
+    cells: Vec<Cell>,
+    ends: Vec<usize>,
+}
+
+impl Rows {
+    pub(crate) fn with_capacity(rows: usize, cells: usize) -> Self {
+        Rows {
+            cells: Vec::with_capacity(cells),
+            ends: Vec::with_capacity(rows),
+        }
+    }
+
+    pub(crate) fn reserve(&mut self, rows: usize, cells: usize) {
+        self.cells.reserve(cells);
+        self.ends.reserve(rows);
+    }
+
+    pub(crate) fn len(&self) -> usize {
+        self.ends.len()
+    }
+
+    pub(crate) fn is_empty(&self) -> bool {
+        self.ends.is_empty()
+    }
+
+    /// Number of rows that fit without reallocating.
+    #[cfg(test)]
+    pub(crate) fn capacity(&self) -> usize {
+        self.ends.capacity()
+    }
+
+    /// Bytes allocated for the rows, including the text of their cells.
+    pub(crate) fn heap_size(&self) -> usize {
+        self.cells.capacity() * core::mem::size_of::<Cell>()
+            + self.ends.capacity() * core::mem::size_of::<usize>()
+            + self.cells.iter().map(Cell::heap_size).sum::<usize>()
+    }
+
+    pub(crate) fn shrink_to_fit(&mut self) {
+        self.cells.shrink_to_fit();
+        self.ends.shrink_to_fit();
+        for cell in self.cells.iter_mut() {
+            cell.shrink_to_fit();
+        }
+    }
+
+    fn start(&self, index: usize) -> usize {
+        match index {
+            0 => 0,
+            i => self.ends[i - 1],
+        }
+    }
+
+    pub(crate) fn get(&self, index: usize) -> Option<&[Cell]> {
+        let end = *self.ends.get(index)?;
+        Some(&self.cells[self.start(index)..end])
+    }
+
+    pub(crate) fn get_mut(&mut self, index: usize) -> Option<&mut [Cell]> {
+        let end = *self.ends.get(index)?;
+        let start = self.start(index);
+        Some(&mut self.cells[start..end])
+    }
+
+    pub(crate) fn iter(&self) -> impl DoubleEndedIterator<Item = &[Cell]> + ExactSizeIterator + Clone + '_ {
+        (0..self.len()).map(|i| &self[i])
+    }
+
+    pub(crate) fn push(&mut self, row: Vec<Cell>) {
+        self.cells.extend(row);
+        self.ends.push(self.cells.len());
+    }
+
+    /// Insert `row` at `index`, moving the cells of later rows along.
+    pub(crate) fn insert(&mut self, index: usize, row: Vec<Cell>) {
+        let start = self.start_or_end(index);
+        let len = row.len();
+        self.cells.splice(start..start, row);
+        self.ends.insert(index, start);
+        for end in self.ends[index..].iter_mut() {
+            *end += len;
+        }
+    }
+
+    /// Where row `index` starts, or the end of the cells if it is one past the last row.
+    fn start_or_end(&self, index: usize) -> usize {
+        if index == self.len() {
+            self.cells.len()
+        } else {
+            self.start(index)
+        }
+    }
+
+    pub(crate) fn remove(&mut self, index: usize) -> Vec<Cell> {
+        let (start, end) = (self.start(index), self.ends[index]);
+        let row: Vec<Cell> = self.cells.drain(start..end).collect();
+        self.ends.remove(index);
+        for e in self.ends[index..].iter_mut() {
+            *e -= row.len();
+        }
+        row
+    }
+
+    pub(crate) fn extend(&mut self, other: Rows) {
+        let offset = self.cells.len();
+        self.cells.extend(other.cells);
+        self.ends.extend(other.ends.into_iter().map(|end| end + offset));
+    }
+
+    pub(crate) fn clear(&mut self) {
+        self.cells.clear();
+        self.ends.clear();
+    }
+
+    pub(crate) fn truncate(&mut self, len: usize) {
+        if len < self.len() {
+            self.cells.truncate(self.start(len));
+            self.ends.truncate(len);
+        }
+    }
+
+    /// Remove the first `n` rows, or every row if there are fewer.
+    pub(crate) fn drain_front(&mut self, n: usize) {
+        let n = core::cmp::min(n, self.len());
+        let start = self.start(n);
+        self.cells.drain(..start);
+        self.ends.drain(..n);
+        for end in self.ends.iter_mut() {
+            *end -= start;
+        }
+    }
+
+    /// A copy of the rows in `start..end`.
+    pub(crate) fn slice(&self, start: usize, end: usize) -> Rows {
+        let offset = self.start(start);
+        Rows {
+            cells: self.cells[offset..self.start_or_end(end)].to_vec(),
+            ends: self.ends[start..end].iter().map(|e| e - offset).collect(),
+        }
+    }
+
+    /// Rearrange the rows so that row `i` is the old row `order[i]`. Rows not
+    /// in `order` are dropped.
+    pub(crate) fn reorder(&mut self, order: &[usize]) {
+        let mut old: Vec<Option<Cell>> = core::mem::take(&mut self.cells).into_iter().map(Some).collect();
+        let ends = core::mem::take(&mut self.ends);
+        for &i in order {
+            let start = if i == 0 { 0 } else { ends[i - 1] };
+            self.cells.extend(old[start..ends[i]].iter_mut().map(|cell| cell.take().expect("each row is moved once")));
+            self.ends.push(self.cells.len());
+        }
+    }
+
+    /// Run `f` on every row as a `Vec`, for edits that change row lengths.
+    pub(crate) fn edit_each<F: FnMut(&mut Vec<Cell>)>(&mut self, mut f: F) {
+        let mut old = core::mem::take(&mut self.cells).into_iter();
+        let ends = core::mem::take(&mut self.ends);
+        let mut row = Vec::new();
+        let mut start = 0;
+        for end in ends {
+            row.extend(old.by_ref().take(end - start));
+            start = end;
+            f(&mut row);
+            self.cells.append(&mut row);
+            self.ends.push(self.cells.len());
+        }
+    }
+}
+
+impl Index<usize> for Rows {
+    type Output = [Cell];
+
+    fn index(&self, index: usize) -> &[Cell] {
+        self.get(index).unwrap_or_else(|| panic!("row index (is {index}) should be < row count (is {})", self.len()))
+    }
+}
+
+impl From<Vec<Vec<Cell>>> for Rows {
+    fn from(rows: Vec<Vec<Cell>>) -> Self {
+        rows.into_iter().collect()
+    }
+}
+
+impl FromIterator<Vec<Cell>> for Rows {
+    fn from_iter<I: IntoIterator<Item = Vec<Cell>>>(iter: I) -> Self {
+        let mut rows = Rows::default();
+        for row in iter {
+            rows.push(row);
+        }
+        rows
+    }
+}
+
+/// Owning iterator over the rows of a [`Rows`], one `Vec` per row.
+#[derive(Debug)]
+pub(crate) struct IntoIter {
+    cells: alloc::vec::IntoIter<Cell>,
+    ends: alloc::vec::IntoIter<usize>,
+    start: usize,
+}
+
+impl Iterator for IntoIter {
+    type Item = Vec<Cell>;
+
+    fn next(&mut self) -> Option<Vec<Cell>> {
+        let end = self.ends.next()?;
+        let row = self.cells.by_ref().take(end - self.start).collect();
+        self.start = end;
+        Some(row)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.ends.size_hint()
+    }
+}
+
+impl IntoIterator for Rows {
+    type Item = Vec<Cell>;
+    type IntoIter = IntoIter;
+
+    fn into_iter(self) -> IntoIter {
+        IntoIter {
+            cells: self.cells.into_iter(),
+            ends: self.ends.into_iter(),
+            start: 0,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Rows;
+    use crate::Cell;
+
+    fn rows(rows: &[&[i64]]) -> Rows {
+        rows.iter().map(|row| row.iter().map(|&n| Cell::from(n)).collect()).collect()
+    }
+
+    #[test]
+    fn test_flat_rows() {
+        let mut table = rows(&[&[1, 2], &[3], &[4, 5, 6]]);
+        assert_eq!(&table[1], &[Cell::from(3)]);
+        table.insert(1, vec![Cell::from(9)]);
+        assert_eq!(table.remove(0), vec![Cell::from(1), Cell::from(2)]);
+        assert_eq!(table, rows(&[&[9], &[3], &[4, 5, 6]]));
+        table.reorder(&[2, 0]);
+        assert_eq!(table, rows(&[&[4, 5, 6], &[9]]));
+        table.extend(rows(&[&[7, 8]]));
+        table.drain_front(1);
+        assert_eq!(table.slice(1, 2), rows(&[&[7, 8]]));
+        table.edit_each(|row| row.push(Cell::Empty));
+        assert_eq!(table.into_iter().collect::<Vec<_>>(), vec![vec![Cell::from(9), Cell::Empty], vec![Cell::from(7), Cell::from(8), Cell::Empty]]);
+    }
+}
@@ -0,0 +1,55 @@
+use rusqlite::types::ValueRef;
+use rusqlite::{Params, Statement};
+
+use crate::{Cell, Config, Header, ModifyRows, Table};
+
+impl<const N: usize> Table<ModifyRows, N> {
+    /// Run a prepared statement and build a table of the results, headed by the
+    /// column names. NULLs are empty cells, shown as the table's
+    /// [`null_text`](Table::null_text).
+    pub fn from_rusqlite<P: Params>(statement: &mut Statement, params: P) -> rusqlite::Result<Self> {
+        let headers: Vec<Header> = statement.column_names().into_iter().map(Into::into).collect();
+        let columns = headers.len();
+        let mut rows = Vec::new();
+        let mut results = statement.query(params)?;
+        while let Some(row) = results.next()? {
+            let cells = (0..columns)
+                .map(|i| row.get_ref(i).map(cell))
+                .collect::<rusqlite::Result<Vec<Cell>>>()?;
+            rows.push(cells);
+        }
+        Ok(Table::from_parts(headers, rows, Config::default()))
+    }
+}
+
+fn cell(value: ValueRef) -> Cell {
+    match value {
+        ValueRef::Null => Cell::Empty,
+        ValueRef::Integer(n) => n.into(),
+        ValueRef::Real(n) => n.into(),
+        ValueRef::Text(text) => String::from_utf8_lossy(text).into_owned().into(),
+        ValueRef::Blob(bytes) => format!("<{} bytes>", bytes.len()).into(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rusqlite::Connection;
+
+    use crate::{ModifyRows, Table};
+
+    #[test]
+    fn test_from_rusqlite() {
+        let conn = Connection::open_in_memory().unwrap();
+        conn.execute_batch("
+            CREATE TABLE hosts (name TEXT, cores INTEGER);
+            INSERT INTO hosts VALUES ('web-1', 4), ('db-1', NULL);
+        ").unwrap();
+        let mut statement = conn.prepare("SELECT name, cores FROM hosts ORDER BY name").unwrap();
+        let table: Table<ModifyRows> = Table::from_rusqlite(&mut statement, []).unwrap().null_text("NULL");
+        assert_eq!(table.to_string(),
+                   "name     cores    \n".to_owned() +
+                       "db-1     NULL     \n" +
+                       "web-1    4        \n");
+    }
+}
@@ -0,0 +1,134 @@
+use serde::de::{self, Visitor};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+use crate::rows::Rows;
+use crate::{Cell, Config, Header, Table};
+
+/// Column widths are derived data, so they are left out of the serialized form and recomputed
+/// when a table is loaded.
+#[derive(Serialize)]
+#[serde(rename = "Table")]
+struct TableRef<'a> {
+    headers: &'a [Header],
+    rows: &'a Rows,
+    skip_header: bool,
+}
+
+#[derive(Deserialize)]
+#[serde(rename = "Table")]
+struct TableOwned {
+    headers: Vec<Header>,
+    rows: Vec<Vec<Cell>>,
+    #[serde(default)]
+    skip_header: bool,
+}
+
+/// Cells serialize as the matching JSON-like scalar, with `Empty` as null.
+impl Serialize for Cell {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        match self {
+            Cell::Str(s) => serializer.serialize_str(s),
+            Cell::Shared(s) => serializer.serialize_str(s),
+            #[cfg(feature = "compact_str")]
+            Cell::Compact(s) => serializer.serialize_str(s),
+            Cell::Int(n) => serializer.serialize_i64(*n),
+            Cell::Float(n) => serializer.serialize_f64(*n),
+            Cell::Bool(b) => serializer.serialize_bool(*b),
+            Cell::Empty => serializer.serialize_none(),
+        }
+    }
+}
+
+struct CellVisitor;
+
+impl<'de> Visitor<'de> for CellVisitor {
+    type Value = Cell;
+
+    fn expecting(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        f.write_str("a string, number, boolean or null")
+    }
+
+    fn visit_str<E: de::Error>(self, v: &str) -> Result<Cell, E> {
+        Ok(v.into())
+    }
+
+    fn visit_string<E: de::Error>(self, v: String) -> Result<Cell, E> {
+        Ok(v.into())
+    }
+
+    fn visit_i64<E: de::Error>(self, v: i64) -> Result<Cell, E> {
+        Ok(v.into())
+    }
+
+    fn visit_u64<E: de::Error>(self, v: u64) -> Result<Cell, E> {
+        Ok(v.into())
+    }
+
+    fn visit_f64<E: de::Error>(self, v: f64) -> Result<Cell, E> {
+        Ok(v.into())
+    }
+
+    fn visit_bool<E: de::Error>(self, v: bool) -> Result<Cell, E> {
+        Ok(v.into())
+    }
+
+    fn visit_none<E: de::Error>(self) -> Result<Cell, E> {
+        Ok(Cell::Empty)
+    }
+
+    fn visit_unit<E: de::Error>(self) -> Result<Cell, E> {
+        Ok(Cell::Empty)
+    }
+}
+
+/// Rows serialize as a sequence of sequences of cells.
+impl Serialize for Rows {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_seq(self.iter())
+    }
+}
+
+impl<'de> Deserialize<'de> for Cell {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        deserializer.deserialize_any(CellVisitor)
+    }
+}
+
+impl<T, const N: usize> Serialize for Table<T, N> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        TableRef {
+            headers: &self.headers,
+            rows: &self.rows,
+            skip_header: self.config.skip_header,
+        }.serialize(serializer)
+    }
+}
+
+impl<'de, T, const N: usize> Deserialize<'de> for Table<T, N> {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let table = TableOwned::deserialize(deserializer)?;
+        let config = Config {
+            skip_header: table.skip_header,
+            ..Config::default()
+        };
+        Ok(Table::from_parts(table.headers, table.rows, config))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{ModifyRows, Row, Table};
+
+    #[test]
+    fn test_round_trip() {
+        let table: Table<ModifyRows> = Table::new()
+            .header("Name")
+            .header("Age")
+            .row(Row::new().cell("Alice").cell(20))
+            .row(Row::new().cell("Bob").cell(None::<i64>));
+        let json = serde_json::to_string(&table).unwrap();
+        assert_eq!(json, r#"{"headers":[{"text":"Name","alignment":"Left"},{"text":"Age","alignment":"Left"}],"rows":[["Alice",20],["Bob",null]],"skip_header":false}"#);
+        let loaded: Table<ModifyRows> = serde_json::from_str(&json).unwrap();
+        assert_eq!(loaded, table);
+    }
+}
@@ -0,0 +1,37 @@
+use sqlx::any::AnyRow;
+use sqlx::{Column, Row};
+
+use crate::{Cell, Config, Header, ModifyRows, Table};
+
+impl<const N: usize> Table<ModifyRows, N> {
+    /// Build a table from query results, with a header per result column. NULL
+    /// becomes an empty cell and binary values show their length.
+    pub fn from_sqlx_rows(rows: &[AnyRow]) -> Result<Self, sqlx::Error> {
+        let headers: Vec<Header> = match rows.first() {
+            Some(row) => row.columns().iter().map(|c| c.name().into()).collect(),
+            None => Vec::new(),
+        };
+        let rows = rows.iter()
+            .map(|row| (0..row.len()).map(|i| decode(row, i)).collect())
+            .collect::<Result<Vec<Vec<Cell>>, _>>()?;
+        Ok(Table::from_parts(headers, rows, Config::default()))
+    }
+}
+
+/// The `Any` driver only reports a handful of types, so try each in turn.
+fn decode(row: &AnyRow, index: usize) -> Result<Cell, sqlx::Error> {
+    if let Ok(value) = row.try_get::<Option<bool>, _>(index) {
+        return Ok(value.into());
+    }
+    if let Ok(value) = row.try_get::<Option<i64>, _>(index) {
+        return Ok(value.into());
+    }
+    if let Ok(value) = row.try_get::<Option<f64>, _>(index) {
+        return Ok(value.into());
+    }
+    if let Ok(value) = row.try_get::<Option<String>, _>(index) {
+        return Ok(value.into());
+    }
+    let bytes = row.try_get::<Option<Vec<u8>>, _>(index)?;
+    Ok(bytes.map(|b| format!("<{} bytes>", b.len())).into())
+}
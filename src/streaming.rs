@@ -0,0 +1,143 @@
+use std::io;
+
+use crate::{ModifyRows, Row, Table};
+
+/// Writes each row as soon as it is pushed, for output that can't wait for
+/// the whole table, such as tailing live data.
+///
+/// Column widths are fixed when the header is written, so set a
+/// [`min_width`](crate::Column::min_width) on the columns, pass the widths
+/// to [`with_widths`](Self::with_widths), or let [`adaptive`](Self::adaptive)
+/// measure the first rows. Longer cells are truncated.
+///
+/// ```
+/// use tabular2::{Column, Row, StreamingTable, Table};
+///
+/// let header = Table::new().header("Time").header(Column::new("Event").min_width(12));
+/// let mut out = StreamingTable::new(header, Vec::new()).unwrap();
+/// out.push_row(Row::new().cell("12:00").cell("started")).unwrap();
+/// let text = String::from_utf8(out.into_inner()).unwrap();
+/// assert_eq!(text, "Time     Event        \n12:00    started      \n");
+/// ```
+#[derive(Debug)]
+pub struct StreamingTable<W: io::Write, const N: usize = 0> {
+    table: Table<ModifyRows, N>,
+    widths: Vec<usize>,
+    writer: W,
+    /// Rows to buffer before the widths are fixed, or 0 once they are.
+    sample: usize,
+}
+
+impl<W: io::Write, const N: usize> StreamingTable<W, N> {
+    /// Take the headers and settings from `table` and write the header line.
+    /// Columns are as wide as they would be for `table` when rendered.
+    pub fn new<T>(table: Table<T, N>, writer: W) -> io::Result<Self> {
+        let widths = table.display_widths();
+        Self::with_widths(table, widths, writer)
+    }
+
+    /// Like [`new`](Self::new), with a width for each column.
+    pub fn with_widths<T>(table: Table<T, N>, widths: Vec<usize>, mut writer: W) -> io::Result<Self> {
+        let table = Table::from_parts(table.headers, Vec::new(), table.config);
+        if !table.config.skip_header && !table.headers.is_empty() {
+            let mut line = String::new();
+            table.write_plain_header(&mut line, &widths).expect("writing to a String can't fail");
+            writer.write_all(line.as_bytes())?;
+        }
+        Ok(StreamingTable { table, widths, writer, sample: 0 })
+    }
+
+    /// Hold back the first `sample` rows, then fix the widths to fit them and
+    /// write everything so far. Nothing is written until then, or until
+    /// [`finish`](Self::finish) if there are fewer rows.
+    pub fn adaptive<T>(table: Table<T, N>, sample: usize, writer: W) -> Self {
+        StreamingTable {
+            table: Table::from_parts(table.headers, Vec::new(), table.config),
+            widths: Vec::new(),
+            writer,
+            sample: sample.max(1),
+        }
+    }
+
+    /// Fix the widths from the buffered rows and write the header and rows.
+    fn lock(&mut self) -> io::Result<()> {
+        self.sample = 0;
+        self.widths = self.table.display_widths();
+        let mut text = String::new();
+        self.table.write_plain(&mut text).expect("writing to a String can't fail");
+        self.table.rows.clear();
+        self.table.meta.clear();
+        self.writer.write_all(text.as_bytes())
+    }
+
+    /// Write `row`. Rows are fitted to the columns by the table's row length
+    /// policy, and a rejected row is an `InvalidInput` error.
+    pub fn push_row(&mut self, row: Row<N>) -> io::Result<()> {
+        let (cells, meta) = self.table.admit(row)
+            .map_err(|e| io::Error::new(io::ErrorKind::InvalidInput, e))?;
+        if self.sample > 0 {
+            self.table.rows.push(cells);
+            self.table.meta.push(meta);
+            if self.table.rows.len() == self.sample {
+                self.lock()?;
+            }
+            return Ok(());
+        }
+        let mut line = String::new();
+        self.table.write_plain_row(&mut line, &cells, &meta.widths, &self.widths).expect("writing to a String can't fail");
+        self.writer.write_all(line.as_bytes())
+    }
+
+    /// The widths columns are written at. Empty while an adaptive table is
+    /// still buffering.
+    pub fn widths(&self) -> &[usize] {
+        &self.widths
+    }
+
+    /// Write any rows still buffered by an adaptive table and return the writer.
+    pub fn finish(mut self) -> io::Result<W> {
+        if self.sample > 0 {
+            self.lock()?;
+        }
+        Ok(self.writer)
+    }
+
+    /// Return the writer, dropping any rows still buffered by an adaptive table.
+    pub fn into_inner(self) -> W {
+        self.writer
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{Row, RowLengthPolicy, StreamingTable, Table};
+
+    #[test]
+    fn test_streaming_table() {
+        let header = Table::new()
+            .header("Host")
+            .header("Message")
+            .row_length_policy(RowLengthPolicy::Error);
+        let mut out = StreamingTable::with_widths(header, vec![6, 8], Vec::new()).unwrap();
+        out.push_row(Row::new().cell("web-1").cell("connection reset")).unwrap();
+        assert!(out.push_row(Row::new().cell("db-1")).is_err());
+        let text = String::from_utf8(out.into_inner()).unwrap();
+        assert_eq!(text, "Host   Message  \nweb-1  connect… \n");
+    }
+
+    #[test]
+    fn test_adaptive_streaming() {
+        let header = Table::new().header("Id").header("Path");
+        let mut out = StreamingTable::adaptive(header, 2, Vec::new());
+        out.push_row(Row::new().cell(1).cell("/srv/data/a")).unwrap();
+        assert!(out.widths().is_empty());
+        out.push_row(Row::new().cell(2).cell("/b")).unwrap();
+        out.push_row(Row::new().cell(3).cell("/srv/data/archive")).unwrap();
+        let text = String::from_utf8(out.finish().unwrap()).unwrap();
+        assert_eq!(text,
+                   "Id       Path        \n".to_owned() +
+                       "1        /srv/data/a \n" +
+                       "2        /b          \n" +
+                       "3        /srv/data/… \n");
+    }
+}
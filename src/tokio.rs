@@ -0,0 +1,43 @@
+use tokio::io::{AsyncWrite, AsyncWriteExt};
+
+use crate::Table;
+
+/// Rendered text is sent to the writer once it grows past this many bytes.
+const CHUNK: usize = 8 * 1024;
+
+impl<T, const N: usize> Table<T, N> {
+    /// Render as `Display` does into an async writer. The table is rendered a
+    /// few rows at a time between writes, so a large table doesn't hold up the
+    /// runtime or sit in memory as one `String`.
+    pub async fn write_to_async<W: AsyncWrite + Unpin>(&self, mut writer: W) -> std::io::Result<()> {
+        let mut lines = self.plain_lines();
+        let mut buf = String::with_capacity(CHUNK);
+        while let Some(line) = lines.write_next(&mut buf) {
+            line.expect("writing to a String can't fail");
+            if buf.len() >= CHUNK {
+                writer.write_all(buf.as_bytes()).await?;
+                buf.clear();
+            }
+        }
+        writer.write_all(buf.as_bytes()).await?;
+        writer.flush().await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{OverflowPolicy, Row, Table};
+
+    #[tokio::test]
+    async fn test_write_to_async() {
+        let mut table = Table::new().header("Id").header("Name").end_header();
+        table.extend((0..2000).map(|i| Row::new().cell(i).cell(format!("row {i}"))));
+        let mut out = Vec::new();
+        table.write_to_async(&mut out).await.unwrap();
+        assert_eq!(String::from_utf8(out).unwrap(), table.to_string());
+        let table = table.fit_width(12, OverflowPolicy::Wrap);
+        let mut out = Vec::new();
+        table.write_to_async(&mut out).await.unwrap();
+        assert_eq!(String::from_utf8(out).unwrap(), table.to_string());
+    }
+}
@@ -0,0 +1,101 @@
+use tracing::Level;
+
+use crate::{Cell, Table};
+
+impl<T, const N: usize> Table<T, N> {
+    /// Emit each row as a `tracing` event with target `tabular2`, a `row`
+    /// field holding its index and a `cells` field holding the row as a JSON
+    /// object keyed by header text, or an array for tables without headers.
+    ///
+    /// Field names of `tracing` events are fixed at compile time, so the cells
+    /// can't each be a field of their own as with [`log_rows`](Self::log_rows);
+    /// JSON log pipelines can still query them inside `cells`. The JSON is
+    /// only written for events a subscriber records.
+    pub fn trace_rows(&self, level: Level) {
+        for (index, row) in self.rows.iter().enumerate() {
+            let cells = RowJson { table: self, row };
+            match level {
+                Level::ERROR => tracing::event!(target: "tabular2", Level::ERROR, row = index, cells = %cells),
+                Level::WARN => tracing::event!(target: "tabular2", Level::WARN, row = index, cells = %cells),
+                Level::INFO => tracing::event!(target: "tabular2", Level::INFO, row = index, cells = %cells),
+                Level::DEBUG => tracing::event!(target: "tabular2", Level::DEBUG, row = index, cells = %cells),
+                Level::TRACE => tracing::event!(target: "tabular2", Level::TRACE, row = index, cells = %cells),
+            }
+        }
+    }
+}
+
+/// Formats a row as JSON when an event is recorded.
+struct RowJson<'a, T, const N: usize> {
+    table: &'a Table<T, N>,
+    row: &'a [Cell],
+}
+
+impl<T, const N: usize> core::fmt::Display for RowJson<'_, T, N> {
+    fn fmt(&self, f: &mut core::fmt::Formatter) -> core::fmt::Result {
+        self.table.write_json_row(f, self.row)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fmt::Debug;
+    use std::sync::{Arc, Mutex};
+
+    use tracing::field::{Field, Visit};
+    use tracing::span::{Attributes, Id, Record};
+    use tracing::{Event, Level, Metadata, Subscriber};
+
+    use crate::{ModifyRows, Row, Table};
+
+    /// Keeps the fields of each event at INFO or above.
+    struct Capture(Arc<Mutex<Vec<String>>>);
+
+    impl Subscriber for Capture {
+        fn enabled(&self, metadata: &Metadata) -> bool {
+            *metadata.level() <= Level::INFO
+        }
+
+        fn new_span(&self, _: &Attributes) -> Id {
+            Id::from_u64(1)
+        }
+
+        fn record(&self, _: &Id, _: &Record) {}
+
+        fn record_follows_from(&self, _: &Id, _: &Id) {}
+
+        fn event(&self, event: &Event) {
+            struct Fields(String);
+            impl Visit for Fields {
+                fn record_debug(&mut self, field: &Field, value: &dyn Debug) {
+                    self.0 += &format!(" {}={value:?}", field.name());
+                }
+            }
+            let mut fields = Fields(event.metadata().target().to_string());
+            event.record(&mut fields);
+            self.0.lock().unwrap().push(fields.0);
+        }
+
+        fn enter(&self, _: &Id) {}
+
+        fn exit(&self, _: &Id) {}
+    }
+
+    #[test]
+    fn test_trace_rows() {
+        let events = Arc::new(Mutex::new(Vec::new()));
+        let table: Table<ModifyRows> = Table::new()
+            .header("Host")
+            .header("Up")
+            .row(Row::new().cell("web-1").cell(true))
+            .row(Row::new().cell("db-1").cell(None::<bool>));
+        tracing::subscriber::with_default(Capture(events.clone()), || {
+            table.trace_rows(Level::INFO);
+            table.trace_rows(Level::DEBUG);
+        });
+        assert_eq!(*events.lock().unwrap(), vec![
+            "tabular2 row=0 cells={\"Host\":\"web-1\",\"Up\":true}",
+            "tabular2 row=1 cells={\"Host\":\"db-1\",\"Up\":null}",
+        ]);
+    }
+}
@@ -0,0 +1,148 @@
+use proc_macro::TokenStream;
+use proc_macro2::TokenStream as TokenStream2;
+use quote::quote;
+use syn::{parse_macro_input, Data, DeriveInput, Error, Fields, LitStr};
+
+/// Derive `tabular2::Tabular`, plus `FromIterator` and `Extend` for `tabular2::Table`.
+///
+/// Each named field becomes a column, headed by the field name. Fields must implement
+/// `Into<tabular2::Cell>`.
+///
+/// `#[tabular(rename_all = "Title Case")]` on the struct converts the field names used as headers
+/// (`Title Case`, `Sentence case`, `UPPER CASE` or `lower case`). Field attributes:
+///
+/// - `#[tabular(rename = "CPU %")]` sets the header text.
+/// - `#[tabular(align = "right")]` aligns the column (`left`, `right` or `center`).
+/// - `#[tabular(skip)]` leaves the field out of the table.
+#[proc_macro_derive(Tabular, attributes(tabular))]
+pub fn derive_tabular(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    expand(input)
+        .unwrap_or_else(|e| e.to_compile_error())
+        .into()
+}
+
+struct FieldAttrs {
+    rename: Option<String>,
+    align: Option<TokenStream2>,
+    skip: bool,
+}
+
+fn field_attrs(field: &syn::Field) -> syn::Result<FieldAttrs> {
+    let mut attrs = FieldAttrs {
+        rename: None,
+        align: None,
+        skip: false,
+    };
+    for attr in field.attrs.iter().filter(|a| a.path().is_ident("tabular")) {
+        attr.parse_nested_meta(|meta| {
+            if meta.path.is_ident("rename") {
+                attrs.rename = Some(meta.value()?.parse::<LitStr>()?.value());
+            } else if meta.path.is_ident("align") {
+                let lit: LitStr = meta.value()?.parse()?;
+                attrs.align = Some(match lit.value().as_str() {
+                    "left" => quote!(::tabular2::Alignment::Left),
+                    "right" => quote!(::tabular2::Alignment::Right),
+                    "center" => quote!(::tabular2::Alignment::Center),
+                    _ => return Err(Error::new_spanned(lit, "expected \"left\", \"right\" or \"center\"")),
+                });
+            } else if meta.path.is_ident("skip") {
+                attrs.skip = true;
+            } else {
+                return Err(meta.error("unknown tabular attribute"));
+            }
+            Ok(())
+        })?;
+    }
+    Ok(attrs)
+}
+
+fn header_case(input: &DeriveInput) -> syn::Result<Option<TokenStream2>> {
+    let mut case = None;
+    for attr in input.attrs.iter().filter(|a| a.path().is_ident("tabular")) {
+        attr.parse_nested_meta(|meta| {
+            if meta.path.is_ident("rename_all") {
+                let lit: LitStr = meta.value()?.parse()?;
+                case = Some(match lit.value().as_str() {
+                    "Title Case" => quote!(::tabular2::HeaderCase::Title),
+                    "Sentence case" => quote!(::tabular2::HeaderCase::Sentence),
+                    "UPPER CASE" => quote!(::tabular2::HeaderCase::Upper),
+                    "lower case" => quote!(::tabular2::HeaderCase::Lower),
+                    _ => return Err(Error::new_spanned(lit, "expected \"Title Case\", \"Sentence case\", \"UPPER CASE\" or \"lower case\"")),
+                });
+            } else {
+                return Err(meta.error("unknown tabular attribute"));
+            }
+            Ok(())
+        })?;
+    }
+    Ok(case)
+}
+
+fn expand(input: DeriveInput) -> syn::Result<TokenStream2> {
+    let name = &input.ident;
+    let fields = match &input.data {
+        Data::Struct(data) => match &data.fields {
+            Fields::Named(fields) => &fields.named,
+            _ => return Err(Error::new_spanned(&input, "Tabular can only be derived for structs with named fields")),
+        },
+        _ => return Err(Error::new_spanned(&input, "Tabular can only be derived for structs")),
+    };
+
+    let case = header_case(&input)?;
+    let mut headers = Vec::new();
+    let mut cells = Vec::new();
+    for field in fields {
+        let attrs = field_attrs(field)?;
+        if attrs.skip {
+            continue;
+        }
+        let ident = field.ident.as_ref().expect("named field");
+        let text = match (attrs.rename, &case) {
+            (Some(rename), _) => quote!(#rename),
+            (None, Some(case)) => {
+                let name = ident.to_string();
+                quote!(&#case.apply(#name))
+            }
+            (None, None) => {
+                let name = ident.to_string();
+                quote!(#name)
+            }
+        };
+        let column = match attrs.align {
+            Some(align) => quote!(::tabular2::Column::new(#text).align(#align)),
+            None => quote!(::tabular2::Column::new(#text)),
+        };
+        headers.push(quote!(::std::convert::Into::<::tabular2::Header>::into(#column)));
+        cells.push(quote!(.cell(self.#ident)));
+    }
+
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+    let mut table_generics = input.generics.clone();
+    table_generics.params.push(syn::parse_quote!(const TABULAR_N: usize));
+    let (table_impl_generics, _, _) = table_generics.split_for_impl();
+
+    Ok(quote! {
+        impl #impl_generics ::tabular2::Tabular for #name #ty_generics #where_clause {
+            fn headers() -> ::std::vec::Vec<::tabular2::Header> {
+                ::std::vec![#(#headers),*]
+            }
+
+            fn into_row<const N: usize>(self) -> ::tabular2::Row<N> {
+                ::tabular2::Row::new()#(#cells)*
+            }
+        }
+
+        impl #table_impl_generics ::std::iter::Extend<#name #ty_generics> for ::tabular2::Table<::tabular2::ModifyRows, TABULAR_N> #where_clause {
+            fn extend<I: ::std::iter::IntoIterator<Item = #name #ty_generics>>(&mut self, iter: I) {
+                self.extend(iter.into_iter().map(::tabular2::Tabular::into_row::<TABULAR_N>));
+            }
+        }
+
+        impl #table_impl_generics ::std::iter::FromIterator<#name #ty_generics> for ::tabular2::Table<::tabular2::ModifyRows, TABULAR_N> #where_clause {
+            fn from_iter<I: ::std::iter::IntoIterator<Item = #name #ty_generics>>(iter: I) -> Self {
+                ::tabular2::Table::from_records(iter)
+            }
+        }
+    })
+}